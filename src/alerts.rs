@@ -0,0 +1,272 @@
+use ratatui::style::{Color, Style};
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    process::{Command, Stdio},
+    thread,
+};
+
+use crate::{
+    config::{self, Threshold},
+    data::{sensors::SensorKind, Data},
+    theme,
+};
+
+/// The most events kept in the alert log.
+const LOG_LEN: usize = 500;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    #[default]
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl Level {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Level::Normal => "normal",
+            Level::Warning => "warning",
+            Level::Critical => "critical",
+        }
+    }
+
+    /// The style of something at this level, `normal` coloured while under the thresholds.
+    pub fn style(&self, normal: Color) -> Style {
+        let theme = theme::get();
+        match self {
+            Level::Normal => Style::new().fg(normal),
+            Level::Warning => theme.warning_style(),
+            Level::Critical => theme.critical_style(),
+        }
+    }
+}
+
+/// A metric moving from one level to another.
+#[derive(Clone, Debug)]
+pub struct Event {
+    pub timestamp: u64,
+    pub metric: String,
+    pub level: Level,
+    pub previous: Level,
+    pub value: f64,
+    pub unit: &'static str,
+}
+
+impl Event {
+    pub fn message(&self) -> String {
+        match self.level {
+            Level::Normal => format!("{} cleared at {:.1}{}", self.metric, self.value, self.unit),
+            level => format!(
+                "{} {} at {:.1}{}",
+                self.metric,
+                level.name(),
+                self.value,
+                self.unit
+            ),
+        }
+    }
+}
+
+/// Every metric with a threshold in `data`, with its current value and unit.
+fn metrics(data: &Data) -> Vec<(String, f64, Threshold, &'static str)> {
+    let thresholds = &config::get().thresholds;
+    let mut metrics = vec![
+        (
+            "CPU".to_string(),
+            data.cpu.usage as f64,
+            thresholds.cpu,
+            "%",
+        ),
+        (
+            "Memory".to_string(),
+            data.memory.mem_percent(),
+            thresholds.memory,
+            "%",
+        ),
+    ];
+    if data.memory.total_swap > 0 {
+        metrics.push((
+            "Swap".to_string(),
+            data.memory.swap_percent(),
+            thresholds.swap,
+            "%",
+        ));
+    }
+    for core in data.cpu.cores.iter() {
+        metrics.push((core.name.clone(), core.usage as f64, thresholds.core, "%"));
+    }
+    for disk in data.disks.iter().filter(|x| x.total_space > 0) {
+        metrics.push((
+            format!("Disk {} free", disk.mount_point.display()),
+            disk.free_space as f64 / disk.total_space as f64 * 100.0,
+            thresholds.disk_free,
+            "%",
+        ));
+    }
+    for sensor in data
+        .sensors
+        .iter()
+        .filter(|x| x.kind == SensorKind::Temperature)
+    {
+        metrics.push((
+            sensor.name(),
+            sensor.value,
+            thresholds.temperature,
+            sensor.kind.unit(),
+        ));
+    }
+    metrics
+}
+
+/// Tracks which metrics are over their thresholds and logs every time one changes level.
+pub struct Alerts {
+    /// The event that put each metric over a threshold, metrics at normal aren't kept.
+    pub active: BTreeMap<String, Event>,
+    /// Newest last.
+    pub log: VecDeque<Event>,
+    /// Run the configured command and notification. Off while replaying, the alerts are from
+    /// another time.
+    hooks: bool,
+}
+
+impl Alerts {
+    pub fn new(hooks: bool) -> Self {
+        Self {
+            active: BTreeMap::new(),
+            log: VecDeque::new(),
+            hooks,
+        }
+    }
+
+    /// The level a metric's alert is at, what its gauge or row is coloured by so it only
+    /// changes colour when the alert does.
+    pub fn level(&self, metric: &str) -> Level {
+        self.active.get(metric).map_or(Level::Normal, |x| x.level)
+    }
+
+    pub fn check(&mut self, data: &Data) {
+        let margin = config::get().alerts.clear_margin;
+        let mut seen = HashSet::new();
+        let mut events = Vec::new();
+        for (metric, value, threshold, unit) in metrics(data) {
+            let previous = self.level(&metric);
+            let level = threshold.level_from(value, previous, margin);
+            seen.insert(metric.clone());
+            if level == previous {
+                continue;
+            }
+
+            let event = Event {
+                timestamp: data.timestamp,
+                metric: metric.clone(),
+                level,
+                previous,
+                value,
+                unit,
+            };
+            match level {
+                Level::Normal => self.active.remove(&metric),
+                _ => self.active.insert(metric, event.clone()),
+            };
+            events.push(event);
+        }
+
+        // A metric that went away (e.g. an unmounted disk) can't clear itself.
+        self.active.retain(|metric, _| seen.contains(metric));
+
+        if self.hooks && !events.is_empty() {
+            run_hooks(&events);
+        }
+        for event in events {
+            if self.log.len() >= LOG_LEN {
+                self.log.pop_front();
+            }
+            self.log.push_back(event);
+        }
+    }
+}
+
+/// Runs the configured command and sends a desktop notification through `notify-send`, once
+/// for all the `events` of a sample so a spike across every core is a single alert. Both are
+/// left to run in the background and failures are ignored, alerts are still logged.
+fn run_hooks(events: &[Event]) {
+    let config = &config::get().alerts;
+    let mut commands = Vec::new();
+    // The first of the most severe events, the one the command's variables describe.
+    let Some(worst) = events.iter().rev().max_by_key(|x| x.level) else {
+        return;
+    };
+    let message = events
+        .iter()
+        .map(|x| x.message())
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if let Some(command) = &config.command {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(command)
+            .env("MONITORS_METRIC", &worst.metric)
+            .env("MONITORS_LEVEL", worst.level.name())
+            .env("MONITORS_PREVIOUS", worst.previous.name())
+            .env("MONITORS_VALUE", format!("{:.1}", worst.value))
+            .env("MONITORS_MESSAGE", &message)
+            .env("MONITORS_COUNT", events.len().to_string());
+        commands.push(cmd);
+    }
+    if config.notify {
+        let urgency = match worst.level {
+            Level::Critical => "critical",
+            _ => "normal",
+        };
+        let mut cmd = Command::new("notify-send");
+        cmd.arg("--app-name=monitors")
+            .arg(format!("--urgency={}", urgency))
+            .arg("Monitors")
+            .arg(&message);
+        commands.push(cmd);
+    }
+
+    for mut cmd in commands {
+        let child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        if let Ok(mut child) = child {
+            thread::spawn(move || child.wait());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{fixture::sample, Core};
+
+    #[test]
+    fn levels_follow_the_alerts() {
+        let mut alerts = Alerts::new(false);
+        let mut data = sample(1, 80.0);
+        data.cpu.cores = (0..2)
+            .map(|i| Core {
+                name: format!("cpu{}", i),
+                usage: 95.0,
+                ..Default::default()
+            })
+            .collect();
+        alerts.check(&data);
+        assert_eq!(alerts.log.len(), 3);
+        assert_eq!(alerts.level("CPU"), Level::Warning);
+        assert_eq!(alerts.level("cpu1"), Level::Warning);
+
+        // Under the threshold but within the margin, so the gauge stays at warning too.
+        alerts.check(&sample(2, 72.0));
+        assert_eq!(alerts.level("CPU"), Level::Warning);
+        assert_eq!(alerts.level("cpu1"), Level::Normal);
+        alerts.check(&sample(3, 60.0));
+        assert_eq!(alerts.level("CPU"), Level::Normal);
+        assert_eq!(alerts.log.len(), 4);
+    }
+}
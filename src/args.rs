@@ -0,0 +1,156 @@
+use std::{path::PathBuf, time::Duration};
+
+pub const HELP: &str = "\
+A CLI tool made using ratatui and rust that allows you to monitor your system information.
+
+Usage: monitors [OPTIONS]
+
+Options:
+  -i, --interval <MS>     Time between samples in milliseconds [default: 1000]
+  -H, --history <N>       Number of samples kept for the history page [default: 100]
+  -p, --page <PAGE>       Page to start on: stats1, stats2, monitor, sensors, alerts,
+                          history, processes
+  -c, --config <FILE>     Config file [default: $XDG_CONFIG_HOME/monitors/config.toml]
+      --sysfs <DIR>       Read hardware details from DIR instead of /sys
+      --theme <NAME>      Colour theme: dark, light, high-contrast, monochrome or one from
+                          the config
+      --json              Print a JSON snapshot of the system and exit
+      --json-lines        Print one JSON snapshot per line every interval
+  -n, --count <N>         Number of snapshots to print [default: 1 for --json]
+      --record <FILE>     Append every sample to FILE while running
+      --replay <FILE>     Replay a recorded FILE instead of sampling this system
+      --dump-default-config
+                          Print the default config and exit
+  -h, --help              Print help
+  -V, --version           Print version";
+
+/// The shortest sampling interval we allow, sysinfo needs some time between refreshes to
+/// compute CPU usage.
+pub const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Command {
+    #[default]
+    Run,
+    Help,
+    Version,
+    DumpDefaultConfig,
+    Json,
+    JsonLines,
+}
+
+/// The parsed command line. Options left unset fall back to the config file.
+#[derive(Clone, Debug, Default)]
+pub struct Args {
+    pub command: Command,
+    pub interval: Option<Duration>,
+    pub history: Option<usize>,
+    pub page: Option<String>,
+    pub config: Option<PathBuf>,
+    pub sysfs: Option<PathBuf>,
+    pub theme: Option<String>,
+    pub count: Option<usize>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+}
+
+impl Args {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg, None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or(format!("missing value for '{}'", flag))
+            };
+
+            match flag.as_str() {
+                "-h" | "--help" => parsed.command = Command::Help,
+                "-V" | "--version" => parsed.command = Command::Version,
+                "--dump-default-config" => parsed.command = Command::DumpDefaultConfig,
+                "--json" => parsed.command = Command::Json,
+                "--json-lines" => parsed.command = Command::JsonLines,
+                "-n" | "--count" => parsed.count = Some(number::<usize>(&flag, &value()?)?),
+                "-i" | "--interval" => {
+                    let ms = number::<u64>(&flag, &value()?)?;
+                    parsed.interval = Some(Duration::from_millis(ms));
+                }
+                "-H" | "--history" => {
+                    parsed.history = Some(number::<usize>(&flag, &value()?)?.max(1))
+                }
+                "-p" | "--page" => parsed.page = Some(value()?),
+                "-c" | "--config" => parsed.config = Some(PathBuf::from(value()?)),
+                "--sysfs" => parsed.sysfs = Some(PathBuf::from(value()?)),
+                "--theme" => parsed.theme = Some(value()?),
+                "--record" => parsed.record = Some(PathBuf::from(value()?)),
+                "--replay" => parsed.replay = Some(PathBuf::from(value()?)),
+                _ => return Err(format!("unexpected argument '{}'", flag)),
+            }
+        }
+
+        if parsed.record.is_some() && parsed.replay.is_some() {
+            return Err("'--record' cannot be used with '--replay'".to_string());
+        }
+
+        Ok(parsed)
+    }
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse::<T>()
+        .map_err(|_| format!("invalid value '{}' for '{}'", value, flag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|x| x.to_string()))
+    }
+
+    #[test]
+    fn parses_flags_and_values() {
+        let args = parse(&[
+            "-i",
+            "500",
+            "--history=300",
+            "-p",
+            "processes",
+            "--json-lines",
+        ]);
+        let args = args.unwrap();
+        assert_eq!(args.interval, Some(Duration::from_millis(500)));
+        assert_eq!(args.history, Some(300));
+        assert_eq!(args.page.as_deref(), Some("processes"));
+        assert_eq!(args.command, Command::JsonLines);
+
+        let args = parse(&["--replay", "session.jsonl.gz", "-H", "0"]).unwrap();
+        assert_eq!(args.replay, Some(PathBuf::from("session.jsonl.gz")));
+        assert_eq!(args.history, Some(1));
+        assert_eq!(args.command, Command::Run);
+        assert_eq!(parse(&[]).unwrap().interval, None);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        let err = parse(&["--frobnicate"]).unwrap_err();
+        assert_eq!(err, "unexpected argument '--frobnicate'");
+        let err = parse(&["-p", "monitor", "--interval"]).unwrap_err();
+        assert_eq!(err, "missing value for '--interval'");
+        let err = parse(&["--count=many"]).unwrap_err();
+        assert_eq!(err, "invalid value 'many' for '--count'");
+        let err = parse(&["--interval", "-5"]).unwrap_err();
+        assert_eq!(err, "invalid value '-5' for '--interval'");
+        assert!(parse(&["--record", "a", "--replay", "b"]).is_err());
+    }
+}
@@ -0,0 +1,483 @@
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::{
+    alerts::Level,
+    data::sensors,
+    pages::{
+        history::CHARTS,
+        monitor::{CoreGroup, MonitorMode},
+        processes::SortColumn,
+    },
+    theme::{Theme, BUILT_IN},
+};
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// The configuration loaded at startup, or the defaults if none has been loaded.
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+pub fn set(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Time between samples in milliseconds.
+    pub interval: u64,
+    /// Number of samples kept for the history page.
+    pub history: usize,
+    /// Hours of per-minute CPU, memory and swap kept once samples leave the history, 0 keeps
+    /// none.
+    pub retention: u64,
+    /// Page to start on.
+    pub page: String,
+    /// Root of the sysfs tree sensors, batteries and the CPU topology are read from. Pointing
+    /// it at a copy lets them be tried without the real hardware.
+    pub sysfs: PathBuf,
+    /// Click, select and scroll with the mouse. Turning it off leaves the mouse to the
+    /// terminal, e.g. for selecting text.
+    pub mouse: bool,
+    pub layout: Layouts,
+    /// A built-in theme or one from `themes`. Unset uses `dark`, or `monochrome` when
+    /// `NO_COLOR` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Changes to the colours of the theme in use.
+    pub colors: Colors,
+    pub themes: BTreeMap<String, CustomTheme>,
+    pub processes: Processes,
+    pub monitor: Monitor,
+    pub thresholds: Thresholds,
+    pub alerts: Alerts,
+    /// Keys for actions in place of the defaults, by scope and action, e.g. `[keys.processes]
+    /// signal = ["x"]`. A binding can be a chord of keys split by spaces like `g g`.
+    pub keys: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            interval: 1000,
+            history: 100,
+            retention: 24,
+            page: "stats1".to_string(),
+            sysfs: PathBuf::from(sensors::SYSFS),
+            mouse: true,
+            layout: Layouts::default(),
+            theme: None,
+            colors: Colors::default(),
+            themes: BTreeMap::new(),
+            processes: Processes::default(),
+            monitor: Monitor::default(),
+            thresholds: Thresholds::default(),
+            alerts: Alerts::default(),
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Grid {
+    pub rows: usize,
+    /// On the stats pages this is the most columns used, narrow terminals get fewer.
+    pub columns: usize,
+}
+
+impl Grid {
+    pub const fn new(rows: usize, columns: usize) -> Self {
+        Self { rows, columns }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Layouts {
+    pub stats1: Grid,
+    pub stats2: Grid,
+    pub monitor: Grid,
+    /// Holds every chart, so it needs at least [`CHARTS`] cells.
+    pub history: Grid,
+}
+
+impl Default for Layouts {
+    fn default() -> Self {
+        Self {
+            stats1: Grid::new(4, 2),
+            stats2: Grid::new(6, 2),
+            monitor: Grid::new(12, 3),
+            history: Grid::new(4, 2),
+        }
+    }
+}
+
+/// Colours that replace the ones of a theme, anything unset is left as the theme has it.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Colors {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight_text: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gauge: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub core_gauge: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub critical: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub good: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub band: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondary: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series: Option<Vec<Color>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heat: Option<Vec<Color>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heat_text: Option<Color>,
+}
+
+impl Colors {
+    pub fn apply(&self, theme: &mut Theme) {
+        let colors = [
+            (self.title, &mut theme.title),
+            (self.border, &mut theme.border),
+            (self.highlight, &mut theme.highlight),
+            (self.highlight_text, &mut theme.highlight_text),
+            (self.gauge, &mut theme.gauge),
+            (self.core_gauge, &mut theme.core_gauge),
+            (self.warning, &mut theme.warning),
+            (self.critical, &mut theme.critical),
+            (self.good, &mut theme.good),
+            (self.cursor, &mut theme.cursor),
+            (self.band, &mut theme.band),
+            (self.primary, &mut theme.primary),
+            (self.secondary, &mut theme.secondary),
+            (self.heat_text, &mut theme.heat_text),
+        ];
+        for (color, slot) in colors {
+            if let Some(color) = color {
+                *slot = color;
+            }
+        }
+        if let Some(series) = &self.series {
+            theme.series = series.clone();
+        }
+        if let Some(heat) = &self.heat {
+            theme.heat = heat.clone();
+        }
+    }
+}
+
+/// A theme of its own, made by changing some of the colours of a built-in one.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomTheme {
+    pub base: String,
+    #[serde(default)]
+    pub colors: Colors,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Processes {
+    pub sort: SortColumn,
+    pub descending: bool,
+    pub tree: bool,
+    /// Show the DISK R/W columns in the process table.
+    pub disk_columns: bool,
+}
+
+impl Default for Processes {
+    fn default() -> Self {
+        Self {
+            sort: SortColumn::Cpu,
+            descending: true,
+            tree: false,
+            disk_columns: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Monitor {
+    pub mode: MonitorMode,
+    pub group: CoreGroup,
+}
+
+/// The values a metric turns to warning and then critical at. When `critical` is below
+/// `warning` lower values are worse, e.g. free disk space.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Threshold {
+    pub warning: f64,
+    pub critical: f64,
+}
+
+impl Threshold {
+    pub const fn new(warning: f64, critical: f64) -> Self {
+        Self { warning, critical }
+    }
+
+    pub fn level(&self, value: f64) -> Level {
+        let over = |limit: f64| match self.critical < self.warning {
+            true => value <= limit,
+            false => value >= limit,
+        };
+        match (over(self.critical), over(self.warning)) {
+            (true, _) => Level::Critical,
+            (_, true) => Level::Warning,
+            _ => Level::Normal,
+        }
+    }
+
+    /// The level of `value` for a metric that was at `previous`. Rising is immediate, but a
+    /// metric only drops a level once it's `margin` past that level's limit so a value sitting
+    /// on a threshold doesn't flip every sample.
+    pub fn level_from(&self, value: f64, previous: Level, margin: f64) -> Level {
+        let level = self.level(value);
+        if level >= previous {
+            return level;
+        }
+        let worse = match self.critical < self.warning {
+            true => value - margin,
+            false => value + margin,
+        };
+        level.max(self.level(worse).min(previous))
+    }
+}
+
+/// Thresholds of the usage percentages, free disk space in percent and temperatures in °C.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Thresholds {
+    pub cpu: Threshold,
+    /// Applies to every core on its own.
+    pub core: Threshold,
+    pub memory: Threshold,
+    pub swap: Threshold,
+    pub disk_free: Threshold,
+    pub temperature: Threshold,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            cpu: Threshold::new(75.0, 90.0),
+            core: Threshold::new(90.0, 98.0),
+            memory: Threshold::new(80.0, 90.0),
+            swap: Threshold::new(50.0, 80.0),
+            disk_free: Threshold::new(15.0, 5.0),
+            temperature: Threshold::new(80.0, 95.0),
+        }
+    }
+}
+
+/// What happens when a metric crosses a threshold, besides the entry on the alerts page.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Alerts {
+    /// Run with `sh -c` once per sample with alerts, the most severe is given in
+    /// `MONITORS_METRIC`, `MONITORS_LEVEL`, `MONITORS_PREVIOUS` and `MONITORS_VALUE`.
+    /// `MONITORS_MESSAGE` has a line for each alert and `MONITORS_COUNT` how many there are.
+    pub command: Option<String>,
+    /// Send a desktop notification with `notify-send`.
+    pub notify: bool,
+    /// How far back past a threshold a metric has to go before its alert clears, in the
+    /// metric's own unit.
+    pub clear_margin: f64,
+}
+
+impl Default for Alerts {
+    fn default() -> Self {
+        Self {
+            command: None,
+            notify: false,
+            clear_margin: 5.0,
+        }
+    }
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/monitors/config.toml`, falling back to `~/.config`.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = env::var_os("XDG_CONFIG_HOME")
+            .filter(|x| !x.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|x| PathBuf::from(x).join(".config")))?;
+        Some(base.join("monitors").join("config.toml"))
+    }
+
+    /// Loads the config at `path`, or at the default path if none is given. Only a missing
+    /// default config falls back to the defaults, a missing explicit path is an error.
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let (path, explicit) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Self::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !explicit => {
+                return Ok(Self::default())
+            }
+            Err(e) => return Err(format!("failed to read {}: {}", path.display(), e)),
+        };
+
+        let config = toml::from_str::<Self>(&text)
+            .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+        config
+            .validate()
+            .map_err(|e| format!("invalid config {}: {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let grids = [
+            ("stats1", self.layout.stats1),
+            ("stats2", self.layout.stats2),
+            ("monitor", self.layout.monitor),
+            ("history", self.layout.history),
+        ];
+        for (name, grid) in grids {
+            if grid.rows == 0 || grid.columns == 0 {
+                return Err(format!(
+                    "layout.{} must have at least one row and column",
+                    name
+                ));
+            }
+        }
+        if self.layout.history.rows * self.layout.history.columns < CHARTS {
+            return Err(format!(
+                "layout.history must have room for the {} charts",
+                CHARTS
+            ));
+        }
+        let thresholds = [
+            ("cpu", self.thresholds.cpu),
+            ("core", self.thresholds.core),
+            ("memory", self.thresholds.memory),
+            ("swap", self.thresholds.swap),
+            ("disk_free", self.thresholds.disk_free),
+            ("temperature", self.thresholds.temperature),
+        ];
+        for (name, threshold) in thresholds {
+            if !threshold.warning.is_finite() || !threshold.critical.is_finite() {
+                return Err(format!("thresholds.{} must be finite numbers", name));
+            }
+        }
+        for (name, custom) in self.themes.iter() {
+            if BUILT_IN.contains(&name.as_str()) {
+                return Err(format!("themes.{} would replace a built-in theme", name));
+            }
+            if !BUILT_IN.contains(&custom.base.as_str()) {
+                return Err(format!(
+                    "themes.{}.base must be one of: {}",
+                    name,
+                    BUILT_IN.join(", ")
+                ));
+            }
+        }
+        if self.history == 0 {
+            return Err("history must be at least 1".to_string());
+        }
+        if !(self.alerts.clear_margin >= 0.0 && self.alerts.clear_margin.is_finite()) {
+            return Err("alerts.clear_margin must be a positive number".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn dump_default() -> String {
+        toml::to_string_pretty(&Self::default()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_rise_with_the_value() {
+        let cpu = Threshold::new(75.0, 90.0);
+        assert_eq!(cpu.level(50.0), Level::Normal);
+        assert_eq!(cpu.level(75.0), Level::Warning);
+        assert_eq!(cpu.level(89.9), Level::Warning);
+        assert_eq!(cpu.level(90.0), Level::Critical);
+    }
+
+    #[test]
+    fn inverted_levels_rise_as_the_value_falls() {
+        let disk_free = Threshold::new(15.0, 5.0);
+        assert_eq!(disk_free.level(50.0), Level::Normal);
+        assert_eq!(disk_free.level(15.0), Level::Warning);
+        assert_eq!(disk_free.level(5.1), Level::Warning);
+        assert_eq!(disk_free.level(5.0), Level::Critical);
+        assert_eq!(disk_free.level(0.0), Level::Critical);
+    }
+
+    #[test]
+    fn levels_only_drop_past_the_margin() {
+        let cpu = Threshold::new(75.0, 90.0);
+        assert_eq!(cpu.level_from(95.0, Level::Normal, 5.0), Level::Critical);
+        assert_eq!(cpu.level_from(74.0, Level::Warning, 5.0), Level::Warning);
+        assert_eq!(cpu.level_from(69.0, Level::Warning, 5.0), Level::Normal);
+        assert_eq!(cpu.level_from(86.0, Level::Critical, 5.0), Level::Critical);
+        assert_eq!(cpu.level_from(80.0, Level::Critical, 5.0), Level::Warning);
+        assert_eq!(cpu.level_from(10.0, Level::Critical, 5.0), Level::Normal);
+        assert_eq!(cpu.level_from(74.0, Level::Warning, 0.0), Level::Normal);
+
+        let disk_free = Threshold::new(15.0, 5.0);
+        assert_eq!(
+            disk_free.level_from(16.0, Level::Warning, 5.0),
+            Level::Warning
+        );
+        assert_eq!(
+            disk_free.level_from(21.0, Level::Warning, 5.0),
+            Level::Normal
+        );
+        assert_eq!(
+            disk_free.level_from(8.0, Level::Critical, 5.0),
+            Level::Critical
+        );
+        assert_eq!(
+            disk_free.level_from(12.0, Level::Critical, 5.0),
+            Level::Warning
+        );
+    }
+
+    #[test]
+    fn history_layouts_must_hold_every_chart() {
+        let mut config = Config::default();
+        assert!(config.validate().is_ok());
+        config.layout.history = Grid::new(3, 2);
+        assert!(config.validate().is_err());
+        config.layout.history = Grid::new(1, 7);
+        assert!(config.validate().is_ok());
+    }
+}
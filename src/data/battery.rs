@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Battery {
+    pub name: String,
+    /// Charge in percent.
+    pub percent: f64,
+    /// The kernel's charging state, e.g. `Charging`, `Discharging` or `Full`.
+    pub state: String,
+    /// Power flowing in or out of the battery in watts.
+    pub watts: Option<f64>,
+    /// Seconds until empty, only known while discharging.
+    pub time_to_empty: Option<u64>,
+    /// Seconds until full, only known while charging.
+    pub time_to_full: Option<u64>,
+    /// Full capacity as a percentage of the design capacity.
+    pub health: Option<f64>,
+    pub cycles: Option<u64>,
+}
+
+fn read_line(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|x| x.trim().to_string())
+        .filter(|x| !x.is_empty())
+}
+
+fn read_number(path: &Path) -> Option<f64> {
+    read_line(path)?.parse::<f64>().ok()
+}
+
+impl Battery {
+    /// Reads a power supply directory, e.g. `/sys/class/power_supply/BAT0`. Batteries report
+    /// either energy (µWh, µW) or charge (µAh, µA), both give the same percentages and times.
+    fn read(dir: &Path) -> Option<Self> {
+        if read_line(&dir.join("type"))? != "Battery" {
+            return None;
+        }
+        if read_line(&dir.join("present")).is_some_and(|x| x == "0") {
+            return None;
+        }
+        let number = |file: &str| read_number(&dir.join(file));
+
+        let (now, full, design, rate) = match number("energy_now") {
+            Some(now) => (
+                Some(now),
+                number("energy_full"),
+                number("energy_full_design"),
+                number("power_now"),
+            ),
+            None => (
+                number("charge_now"),
+                number("charge_full"),
+                number("charge_full_design"),
+                number("current_now"),
+            ),
+        };
+        // Some drivers report a negative rate while discharging.
+        let rate = rate.map(f64::abs).filter(|x| *x > 0.0);
+        let state = read_line(&dir.join("status")).unwrap_or("Unknown".to_string());
+
+        let percent = match (number("capacity"), now, full) {
+            (Some(capacity), _, _) => capacity,
+            (None, Some(now), Some(full)) if full > 0.0 => now / full * 100.0,
+            _ => return None,
+        };
+        let watts = match (
+            number("power_now"),
+            number("current_now"),
+            number("voltage_now"),
+        ) {
+            (Some(power), _, _) => Some(power.abs() / 1e6),
+            (None, Some(current), Some(voltage)) => Some((current * voltage).abs() / 1e12),
+            _ => None,
+        };
+        // µWh / µW (or µAh / µA) is in hours.
+        let time_to = |amount: f64| rate.map(|rate| (amount / rate * 3600.0) as u64);
+        let time_to_empty = match state.as_str() {
+            "Discharging" => now.and_then(time_to),
+            _ => None,
+        };
+        let time_to_full = match (state.as_str(), now, full) {
+            ("Charging", Some(now), Some(full)) => time_to((full - now).max(0.0)),
+            _ => None,
+        };
+
+        Some(Self {
+            name: dir.file_name()?.to_string_lossy().to_string(),
+            percent: percent.clamp(0.0, 100.0),
+            time_to_empty,
+            time_to_full,
+            state,
+            watts,
+            health: match (full, design) {
+                (Some(full), Some(design)) if design > 0.0 => Some(full / design * 100.0),
+                _ => None,
+            },
+            cycles: number("cycle_count").filter(|x| *x > 0.0).map(|x| x as u64),
+        })
+    }
+
+    /// Reads every battery under `<root>/class/power_supply`, chargers and other supplies
+    /// are skipped.
+    pub fn get_vec(root: &Path) -> Vec<Self> {
+        let mut supplies = fs::read_dir(root.join("class").join("power_supply"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|x| x.path())
+            .collect::<Vec<PathBuf>>();
+        supplies.sort();
+        supplies.iter().filter_map(|x| Self::read(x)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixture::TempDir;
+
+    #[test]
+    fn reads_batteries() {
+        let root = TempDir::new("battery");
+        root.write(
+            "class/power_supply/AC",
+            &[("type", "Mains"), ("online", "1")],
+        );
+        // Energy in µWh and µW, discharging at 10 W.
+        root.write(
+            "class/power_supply/BAT0",
+            &[
+                ("type", "Battery"),
+                ("present", "1"),
+                ("status", "Discharging"),
+                ("capacity", "50"),
+                ("energy_now", "25000000"),
+                ("energy_full", "50000000"),
+                ("energy_full_design", "62500000"),
+                ("power_now", "10000000"),
+                ("cycle_count", "120"),
+            ],
+        );
+        // Charge in µAh and µA without a capacity, with a negative current.
+        root.write(
+            "class/power_supply/BAT1",
+            &[
+                ("type", "Battery"),
+                ("status", "Charging"),
+                ("charge_now", "1000000"),
+                ("charge_full", "4000000"),
+                ("charge_full_design", "4000000"),
+                ("current_now", "-1000000"),
+                ("voltage_now", "12000000"),
+                ("cycle_count", "0"),
+            ],
+        );
+        root.write(
+            "class/power_supply/BAT2",
+            &[("type", "Battery"), ("present", "0"), ("capacity", "90")],
+        );
+
+        let batteries = Battery::get_vec(root.path());
+        assert_eq!(
+            batteries,
+            vec![
+                Battery {
+                    name: "BAT0".to_string(),
+                    percent: 50.0,
+                    state: "Discharging".to_string(),
+                    watts: Some(10.0),
+                    time_to_empty: Some(9000),
+                    time_to_full: None,
+                    health: Some(80.0),
+                    cycles: Some(120),
+                },
+                Battery {
+                    name: "BAT1".to_string(),
+                    percent: 25.0,
+                    state: "Charging".to_string(),
+                    watts: Some(12.0),
+                    time_to_empty: None,
+                    time_to_full: Some(10800),
+                    health: Some(100.0),
+                    cycles: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::{fs, path::PathBuf, time::Duration};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Everything we know about a single process, gathered on demand for the detail view.
+#[derive(Clone, Debug)]
+pub struct ProcessDetails {
+    pub pid: Pid,
+    pub name: String,
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+    pub exe: Option<PathBuf>,
+    pub environment: Vec<String>,
+    pub status: String,
+    pub threads: Option<usize>,
+    pub parent: Option<Pid>,
+    /// Seconds since the epoch, with the PID this tells the process apart from a later one
+    /// given the same PID.
+    pub start_time: u64,
+    pub run_time: u64,
+    /// Bytes per second since the previous details, 0 until there are some.
+    pub read_rate: f64,
+    pub write_rate: f64,
+    pub total_read_bytes: u64,
+    pub total_written_bytes: u64,
+    pub open_files: Vec<String>,
+    pub limits: Vec<Limit>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Limit {
+    pub name: String,
+    pub soft: String,
+    pub hard: String,
+    pub units: String,
+}
+
+impl ProcessDetails {
+    /// Gathers the details with a fresh `System`, so the disk usage is only good for its
+    /// totals. The rates come from `rates_since` with the previous details.
+    pub fn new(pid: Pid) -> Option<Self> {
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::everything(),
+        );
+        let process = sys.process(pid)?;
+        let lossy = |x: &[std::ffi::OsString]| -> Vec<String> {
+            x.iter().map(|x| x.to_string_lossy().to_string()).collect()
+        };
+        let disk = process.disk_usage();
+
+        Some(Self {
+            pid,
+            name: process.name().to_string_lossy().to_string(),
+            args: lossy(process.cmd()),
+            cwd: process.cwd().map(|x| x.to_path_buf()),
+            exe: process.exe().map(|x| x.to_path_buf()),
+            environment: lossy(process.environ()),
+            status: process.status().to_string(),
+            threads: process.tasks().map(|x| x.len()),
+            parent: process.parent(),
+            start_time: process.start_time(),
+            run_time: process.run_time(),
+            read_rate: 0.0,
+            write_rate: 0.0,
+            total_read_bytes: disk.total_read_bytes,
+            total_written_bytes: disk.total_written_bytes,
+            open_files: open_files(pid),
+            limits: limits(pid),
+        })
+    }
+
+    /// Sets the disk rates from the totals of `previous`, gathered `elapsed` earlier.
+    pub fn rates_since(&mut self, previous: &ProcessDetails, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        if seconds <= 0.0 {
+            return;
+        }
+        let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / seconds;
+        self.read_rate = rate(self.total_read_bytes, previous.total_read_bytes);
+        self.write_rate = rate(self.total_written_bytes, previous.total_written_bytes);
+    }
+}
+
+/// The targets of the process's file descriptors, from `/proc/<pid>/fd`.
+fn open_files(pid: Pid) -> Vec<String> {
+    let Ok(dir) = fs::read_dir(format!("/proc/{}/fd", pid)) else {
+        return Vec::new();
+    };
+
+    let mut files = dir
+        .flatten()
+        .filter_map(|x| {
+            let fd = x.file_name().to_string_lossy().parse::<u32>().ok()?;
+            let target = fs::read_link(x.path()).ok()?;
+            Some((fd, target.to_string_lossy().to_string()))
+        })
+        .collect::<Vec<(u32, String)>>();
+    files.sort();
+    files
+        .into_iter()
+        .map(|(fd, target)| format!("{fd}: {target}"))
+        .collect()
+}
+
+/// The resource limits from `/proc/<pid>/limits`. The file is a table whose columns are
+/// aligned with spaces, and the names themselves contain spaces, so it is split by the
+/// positions of the header columns.
+fn limits(pid: Pid) -> Vec<Limit> {
+    let Ok(text) = fs::read_to_string(format!("/proc/{}/limits", pid)) else {
+        return Vec::new();
+    };
+    let mut lines = text.lines();
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let (Some(soft), Some(hard), Some(units)) = (
+        header.find("Soft Limit"),
+        header.find("Hard Limit"),
+        header.find("Units"),
+    ) else {
+        return Vec::new();
+    };
+
+    let column = |line: &str, start: usize, end: usize| -> String {
+        line.get(start..end.min(line.len()))
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+
+    lines
+        .map(|line| Limit {
+            name: column(line, 0, soft),
+            soft: column(line, soft, hard),
+            hard: column(line, hard, units),
+            units: column(line, units, line.len()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    #[test]
+    fn rates_are_per_second_since_the_previous_details() {
+        let previous = ProcessDetails::new(Pid::from_u32(process::id())).unwrap();
+        let mut details = previous.clone();
+        details.total_read_bytes += 4096;
+        details.total_written_bytes += 1024;
+        details.rates_since(&previous, Duration::from_secs(2));
+
+        assert_eq!(details.read_rate, 2048.0);
+        assert_eq!(details.write_rate, 512.0);
+        assert_ne!(details.read_rate, details.total_read_bytes as f64);
+
+        // A refresh with no time in between keeps the rates it had.
+        let mut again = details.clone();
+        again.rates_since(&details, Duration::ZERO);
+        assert_eq!(again.read_rate, 2048.0);
+    }
+}
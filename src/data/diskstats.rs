@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    time::{Duration, Instant},
+};
+
+/// `/proc/diskstats` always counts in 512 byte sectors, whatever the device's sector size.
+const SECTOR_SIZE: u64 = 512;
+
+/// The I/O activity of a block device between two samples.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiskIo {
+    pub name: String,
+    /// Bytes read per second.
+    pub read_rate: f64,
+    /// Bytes written per second.
+    pub write_rate: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    /// Percentage of the time the device was busy.
+    pub utilisation: f64,
+    /// Bytes read since monitoring started.
+    pub total_read: u64,
+    /// Bytes written since monitoring started.
+    pub total_written: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Counters {
+    reads: u64,
+    read_sectors: u64,
+    writes: u64,
+    written_sectors: u64,
+    busy_ms: u64,
+}
+
+/// Parses the counters of every block device in `/proc/diskstats`.
+fn parse(text: &str) -> HashMap<String, Counters> {
+    text.lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            let number = |i: usize| fields.get(i).and_then(|x| x.parse::<u64>().ok());
+            Some((
+                fields.get(2)?.to_string(),
+                Counters {
+                    reads: number(3)?,
+                    read_sectors: number(5)?,
+                    writes: number(7)?,
+                    written_sectors: number(9)?,
+                    busy_ms: number(12)?,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn read() -> HashMap<String, Counters> {
+    fs::read_to_string("/proc/diskstats")
+        .map(|x| parse(&x))
+        .unwrap_or_default()
+}
+
+/// Keeps the previous `/proc/diskstats` counters so the next sample can turn them into rates.
+pub struct DiskIoSampler {
+    start: HashMap<String, Counters>,
+    previous: HashMap<String, Counters>,
+    current: HashMap<String, Counters>,
+    refreshed: Instant,
+    elapsed: Duration,
+}
+
+impl Default for DiskIoSampler {
+    fn default() -> Self {
+        let counters = read();
+        Self {
+            start: counters.clone(),
+            previous: counters.clone(),
+            current: counters,
+            refreshed: Instant::now(),
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl DiskIoSampler {
+    pub fn refresh(&mut self) {
+        self.previous = std::mem::replace(&mut self.current, read());
+        self.elapsed = self.refreshed.elapsed();
+        self.refreshed = Instant::now();
+    }
+
+    /// The activity of every device that has done any I/O since monitoring started, so the
+    /// unused loop and ram devices are left out.
+    pub fn get_vec(&mut self) -> Vec<DiskIo> {
+        let seconds = self.elapsed.as_secs_f64();
+        let rate = |x: u64| match seconds > 0.0 {
+            true => x as f64 / seconds,
+            false => 0.0,
+        };
+
+        let mut disks = Vec::new();
+        for (name, now) in self.current.iter() {
+            let start = *self.start.entry(name.clone()).or_insert(*now);
+            let before = self.previous.get(name).copied().unwrap_or(*now);
+            let total_read = now.read_sectors.saturating_sub(start.read_sectors) * SECTOR_SIZE;
+            let total_written =
+                now.written_sectors.saturating_sub(start.written_sectors) * SECTOR_SIZE;
+            if total_read == 0 && total_written == 0 && now.reads == 0 && now.writes == 0 {
+                continue;
+            }
+
+            disks.push(DiskIo {
+                name: name.clone(),
+                read_rate: rate(now.read_sectors.saturating_sub(before.read_sectors) * SECTOR_SIZE),
+                write_rate: rate(
+                    now.written_sectors.saturating_sub(before.written_sectors) * SECTOR_SIZE,
+                ),
+                read_iops: rate(now.reads.saturating_sub(before.reads)),
+                write_iops: rate(now.writes.saturating_sub(before.writes)),
+                utilisation: match seconds > 0.0 {
+                    true => (now.busy_ms.saturating_sub(before.busy_ms) as f64 / 10.0 / seconds)
+                        .min(100.0),
+                    false => 0.0,
+                },
+                total_read,
+                total_written,
+            });
+        }
+        disks.sort_by(|a, b| a.name.cmp(&b.name));
+        disks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISKSTATS: &str = "   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+ 259       0 nvme0n1 1000 20 8000 300 2000 40 16000 500 0 900 800 0 0 0 0 0 0
+ 259       1 nvme0n1p1 10 0 80 3 0 0 0 0 0 5 3
+   8       0 sda 1 2
+";
+
+    #[test]
+    fn parses_diskstats() {
+        let counters = parse(DISKSTATS);
+        assert_eq!(counters.len(), 3);
+        let disk = counters["nvme0n1"];
+        assert_eq!(
+            (
+                disk.reads,
+                disk.read_sectors,
+                disk.writes,
+                disk.written_sectors,
+                disk.busy_ms
+            ),
+            (1000, 8000, 2000, 16000, 900)
+        );
+        assert_eq!(counters["nvme0n1p1"].busy_ms, 5);
+        assert!(!counters.contains_key("sda"));
+    }
+
+    #[test]
+    fn turns_counters_into_rates() {
+        let start = parse(DISKSTATS);
+        let previous = start.clone();
+        let mut current = start.clone();
+        if let Some(disk) = current.get_mut("nvme0n1") {
+            disk.reads += 200;
+            disk.read_sectors += 4096;
+            disk.writes += 100;
+            disk.written_sectors += 2048;
+            disk.busy_ms += 1000;
+        }
+        let mut sampler = DiskIoSampler {
+            start,
+            previous,
+            current,
+            refreshed: Instant::now(),
+            elapsed: Duration::from_secs(2),
+        };
+
+        let disks = sampler.get_vec();
+        // The idle loop device is left out.
+        assert_eq!(
+            disks.iter().map(|x| x.name.as_str()).collect::<Vec<&str>>(),
+            ["nvme0n1", "nvme0n1p1"]
+        );
+        assert_eq!(
+            disks[0],
+            DiskIo {
+                name: "nvme0n1".to_string(),
+                read_rate: 4096.0 * 512.0 / 2.0,
+                write_rate: 2048.0 * 512.0 / 2.0,
+                read_iops: 100.0,
+                write_iops: 50.0,
+                utilisation: 50.0,
+                total_read: 4096 * 512,
+                total_written: 2048 * 512,
+            }
+        );
+        assert_eq!(disks[1].read_rate, 0.0);
+    }
+}
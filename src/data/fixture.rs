@@ -0,0 +1,63 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use crate::data::{CpuInfo, Data, Memory, Summary};
+
+/// A sample taken at `timestamp` with `cpu` percent used and a quarter of the memory.
+pub fn sample(timestamp: u64, cpu: f32) -> Data {
+    Data {
+        timestamp,
+        cpu: CpuInfo {
+            usage: cpu,
+            ..Default::default()
+        },
+        processes: Vec::new(),
+        disks: Vec::new(),
+        memory: Memory {
+            used_mem: 1,
+            total_mem: 4,
+            ..Default::default()
+        },
+        networks: Vec::new(),
+        disk_io: Vec::new(),
+        sensors: Vec::new(),
+        batteries: Vec::new(),
+        summary: Summary::default(),
+    }
+}
+
+/// A directory of fake files for the parser tests. It's removed when dropped, so a failing
+/// assert doesn't leave it behind.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `name` keeps the directories of tests running at the same time apart.
+    pub fn new(name: &str) -> Self {
+        let root = env::temp_dir().join(format!("monitors-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        Self(root)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Writes each file in `dir` with its line of text, creating the directories.
+    pub fn write(&self, dir: &str, files: &[(&str, &str)]) {
+        let dir = self.0.join(dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (name, text) in files {
+            fs::write(dir.join(name), format!("{}\n", text)).unwrap();
+        }
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
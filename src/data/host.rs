@@ -0,0 +1,135 @@
+use hw_linux::{
+    cpu::CpuInfo,
+    environment::{packages::PackageManagers, EnvironmentInfo, KernelInfo},
+    gpu::Gpus,
+    host::HostInfo,
+    InfoTrait,
+};
+use serde::{Deserialize, Serialize};
+
+/// What the stats pages show about the machine besides its samples. It's read once at
+/// startup, it takes a few commands to gather, and a recording keeps it in its header so a
+/// replay shows the machine it was recorded on.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Host {
+    /// The rest is only read on Linux, elsewhere it's all empty.
+    pub linux: bool,
+    pub system: System,
+    pub kernel: Kernel,
+    pub environment: Environment,
+    pub packages: Vec<Packages>,
+    pub gpus: Vec<Gpu>,
+    pub cpu: Cpu,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct System {
+    pub model: Option<String>,
+    pub architecture: Option<String>,
+    pub vendor: Option<String>,
+    pub os: Option<String>,
+    pub distro: Option<String>,
+    pub desktop_env: Option<String>,
+    pub win_manager: Option<String>,
+    pub session: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Kernel {
+    pub version: Option<String>,
+    pub release: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Environment {
+    pub user: Option<String>,
+    pub shell: Option<String>,
+    pub term: Option<String>,
+}
+
+/// The packages installed with a package manager.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Packages {
+    pub manager: String,
+    pub count: usize,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Gpu {
+    pub name: Option<String>,
+    pub vendor: Option<String>,
+    pub driver: Option<String>,
+}
+
+/// The CPU details that `sysinfo` doesn't give, with the frequency in MHz.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Cpu {
+    pub cores: Option<usize>,
+    pub threads: Option<usize>,
+    pub cache: Option<f64>,
+    pub max_frequency: Option<f64>,
+}
+
+impl Host {
+    pub fn get() -> Self {
+        let packages = PackageManagers::get()
+            .unwrap_or_default()
+            .0
+            .into_iter()
+            .map(|x| Packages {
+                manager: x.name,
+                count: x.packages,
+            })
+            .collect();
+        if !hw_linux::is_linux().unwrap_or(false) {
+            return Self {
+                packages,
+                ..Default::default()
+            };
+        }
+
+        let system = HostInfo::get().unwrap_or_default();
+        let kernel = KernelInfo::get().unwrap_or_default();
+        let environment = EnvironmentInfo::get().unwrap_or_default();
+        let cpu = CpuInfo::get().unwrap_or_default();
+        Self {
+            linux: true,
+            system: System {
+                model: system.model,
+                architecture: system.architecture,
+                vendor: system.vendor,
+                os: system.os,
+                distro: system.distro,
+                desktop_env: system.desktop_env,
+                win_manager: system.win_manager,
+                session: system.session,
+            },
+            kernel: Kernel {
+                version: kernel.version,
+                release: kernel.release,
+            },
+            environment: Environment {
+                user: environment.user,
+                shell: environment.shell,
+                term: environment.term,
+            },
+            packages,
+            gpus: Gpus::get()
+                .unwrap_or_default()
+                .0
+                .into_iter()
+                .map(|x| Gpu {
+                    name: x.name,
+                    vendor: x.vendor,
+                    driver: x.driver,
+                })
+                .collect(),
+            cpu: Cpu {
+                cores: cpu.cores,
+                threads: cpu.threads,
+                cache: cpu.cache,
+                max_frequency: cpu.max_freq,
+            },
+        }
+    }
+}
@@ -1,16 +1,102 @@
-use std::{ffi::OsString, path::PathBuf};
-use sysinfo::{DiskKind, IpNetwork, MacAddr, Pid, System, Users};
+pub mod battery;
+pub mod details;
+pub mod diskstats;
+#[cfg(test)]
+pub mod fixture;
+pub mod host;
+pub mod remote;
+pub mod sensors;
+pub mod signal;
+pub mod store;
+pub mod topology;
 
-#[derive(Clone, Debug)]
+use hw_linux::InfoTrait;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use sysinfo::{DiskKind, IpNetwork, MacAddr, Pid, ProcessStatus, System, ThreadKind, Users};
+
+use crate::data::{
+    battery::Battery,
+    diskstats::{DiskIo, DiskIoSampler},
+    remote::{ip_networks, mac_addr, option_pid, os_string, pid, DiskKindDef},
+    sensors::{Sensor, SensorSampler},
+    topology::Topology,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Data {
+    /// Milliseconds since the Unix epoch when the sample was taken.
+    pub timestamp: u64,
     pub cpu: CpuInfo,
     pub processes: Vec<Process>,
     pub disks: Vec<Disk>,
     pub memory: Memory,
     pub networks: Vec<Network>,
+    #[serde(default)]
+    pub disk_io: Vec<DiskIo>,
+    #[serde(default)]
+    pub sensors: Vec<Sensor>,
+    #[serde(default)]
+    pub batteries: Vec<Battery>,
+    #[serde(default)]
+    pub summary: Summary,
+}
+
+/// Milliseconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_millis() as u64)
+}
+
+/// The htop style overview of the whole system.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Summary {
+    /// The 1, 5 and 15 minute load averages.
+    pub load_average: [f64; 3],
+    /// Seconds since boot.
+    pub uptime: u64,
+    /// Processes, not counting their threads.
+    pub tasks: usize,
+    pub running: usize,
+    pub sleeping: usize,
+    pub zombie: usize,
+    /// Userland threads across all processes.
+    pub threads: usize,
 }
 
-#[derive(Default, Clone, Debug)]
+impl Summary {
+    fn new(sys: &System) -> Self {
+        let load = System::load_average();
+        let mut summary = Self {
+            load_average: [load.one, load.five, load.fifteen],
+            uptime: System::uptime(),
+            ..Default::default()
+        };
+
+        for process in sys.processes().values() {
+            if process.thread_kind() == Some(ThreadKind::Userland) {
+                summary.threads += 1;
+                continue;
+            }
+            summary.tasks += 1;
+            match process.status() {
+                ProcessStatus::Run => summary.running += 1,
+                ProcessStatus::Sleep | ProcessStatus::Idle => summary.sleeping += 1,
+                ProcessStatus::Zombie => summary.zombie += 1,
+                _ => {}
+            }
+        }
+        summary
+    }
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct Memory {
     pub used_swap: u64,
     pub total_swap: u64,
@@ -18,11 +104,36 @@ pub struct Memory {
     pub total_mem: u64,
 }
 
-#[derive(Default, Clone, Debug)]
+fn percent(used: u64, total: u64) -> f64 {
+    match total {
+        0 => 0.0,
+        _ => used as f64 / total as f64 * 100.0,
+    }
+}
+
+impl Memory {
+    /// Used memory in percent.
+    pub fn mem_percent(&self) -> f64 {
+        percent(self.used_mem, self.total_mem)
+    }
+
+    /// Used swap in percent, 0 without any swap.
+    pub fn swap_percent(&self) -> f64 {
+        percent(self.used_swap, self.total_swap)
+    }
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct Core {
     pub name: String,
     pub usage: f32,
     pub frequency: u64,
+    /// The physical package the core is on.
+    #[serde(default)]
+    pub socket: Option<usize>,
+    /// The NUMA node the core belongs to.
+    #[serde(default)]
+    pub node: Option<usize>,
 }
 
 impl From<&sysinfo::Cpu> for Core {
@@ -31,19 +142,25 @@ impl From<&sysinfo::Cpu> for Core {
             name: value.name().to_string(),
             usage: value.cpu_usage(),
             frequency: value.frequency(),
+            socket: None,
+            node: None,
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Disk {
     pub name: String,
     pub mount_point: PathBuf,
+    #[serde(with = "DiskKindDef")]
     pub kind: DiskKind,
     pub total_space: u64,
     pub free_space: u64,
     pub read_only: bool,
     pub removable: bool,
+    /// The block device name used in `/proc/diskstats`, e.g. `sda1` or `dm-0`.
+    #[serde(default)]
+    pub device: Option<String>,
 }
 
 impl From<&sysinfo::Disk> for Disk {
@@ -56,6 +173,9 @@ impl From<&sysinfo::Disk> for Disk {
             free_space: value.available_space(),
             read_only: value.is_read_only(),
             removable: value.is_removable(),
+            device: std::fs::canonicalize(value.name())
+                .ok()
+                .and_then(|x| Some(x.file_name()?.to_string_lossy().to_string())),
         }
     }
 }
@@ -69,68 +189,232 @@ impl Disk {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Network {
     pub name: String,
+    #[serde(with = "ip_networks")]
     pub ip_addresses: Vec<IpNetwork>,
+    #[serde(with = "mac_addr")]
     pub mac_address: MacAddr,
+    /// Bytes received since the previous sample.
     pub received: u64,
+    /// Bytes transmitted since the previous sample.
     pub transmitted: u64,
+    /// Bytes received per second.
+    #[serde(default)]
+    pub rx_rate: f64,
+    /// Bytes transmitted per second.
+    #[serde(default)]
+    pub tx_rate: f64,
+    #[serde(default)]
+    pub rx_packet_rate: f64,
+    #[serde(default)]
+    pub tx_packet_rate: f64,
+    /// Receive errors since monitoring started.
+    #[serde(default)]
+    pub rx_errors: u64,
+    /// Transmit errors since monitoring started.
+    #[serde(default)]
+    pub tx_errors: u64,
+    /// Bytes received since monitoring started.
+    #[serde(default)]
+    pub total_received: u64,
+    /// Bytes transmitted since monitoring started.
+    #[serde(default)]
+    pub total_transmitted: u64,
 }
 
-impl From<(&String, &sysinfo::NetworkData)> for Network {
-    fn from(value: (&String, &sysinfo::NetworkData)) -> Self {
-        let mut ips: Vec<IpNetwork> = value
-            .1
-            .ip_networks()
-            .into_iter()
-            .map(|x| x.clone())
-            .collect();
-        ips.sort_by(|a, b| a.cmp(b));
+/// The lifetime counters of an interface.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct NetworkCounters {
+    received: u64,
+    transmitted: u64,
+    rx_errors: u64,
+    tx_errors: u64,
+}
+
+impl From<&sysinfo::NetworkData> for NetworkCounters {
+    fn from(value: &sysinfo::NetworkData) -> Self {
         Self {
-            name: value.0.to_string(),
-            ip_addresses: ips,
-            mac_address: value.1.mac_address(),
-            received: value.1.received(),
-            transmitted: value.1.transmitted(),
+            received: value.total_received(),
+            transmitted: value.total_transmitted(),
+            rx_errors: value.total_errors_on_received(),
+            tx_errors: value.total_errors_on_transmitted(),
         }
     }
 }
 
+/// What the counters of an interface went up by since we first saw it.
+#[derive(Clone, Copy, Debug, Default)]
+struct NetworkTotals {
+    last: NetworkCounters,
+    total: NetworkCounters,
+}
+
+impl NetworkTotals {
+    fn new(now: NetworkCounters) -> Self {
+        Self {
+            last: now,
+            total: NetworkCounters::default(),
+        }
+    }
+
+    /// Adds what the counters went up by since the last update and returns the totals. A
+    /// counter that went back was reset, e.g. by a VPN reconnecting, and counts from 0.
+    fn update(&mut self, now: NetworkCounters) -> NetworkCounters {
+        let add = |total: &mut u64, last: u64, now: u64| {
+            *total += match now >= last {
+                true => now - last,
+                false => now,
+            }
+        };
+        add(&mut self.total.received, self.last.received, now.received);
+        add(
+            &mut self.total.transmitted,
+            self.last.transmitted,
+            now.transmitted,
+        );
+        add(
+            &mut self.total.rx_errors,
+            self.last.rx_errors,
+            now.rx_errors,
+        );
+        add(
+            &mut self.total.tx_errors,
+            self.last.tx_errors,
+            now.tx_errors,
+        );
+        self.last = now;
+        self.total
+    }
+}
+
 impl Network {
-    fn get_vec_from_sysinfo() -> Vec<Self> {
+    fn new(
+        name: &str,
+        data: &sysinfo::NetworkData,
+        elapsed: Duration,
+        totals: NetworkCounters,
+    ) -> Self {
+        let mut ips: Vec<IpNetwork> = data.ip_networks().to_vec();
+        ips.sort();
+        let rate = |x: u64| match elapsed.is_zero() {
+            true => 0.0,
+            false => x as f64 / elapsed.as_secs_f64(),
+        };
+        Self {
+            name: name.to_string(),
+            ip_addresses: ips,
+            mac_address: data.mac_address(),
+            received: data.received(),
+            transmitted: data.transmitted(),
+            rx_rate: rate(data.received()),
+            tx_rate: rate(data.transmitted()),
+            rx_packet_rate: rate(data.packets_received()),
+            tx_packet_rate: rate(data.packets_transmitted()),
+            rx_errors: totals.rx_errors,
+            tx_errors: totals.tx_errors,
+            total_received: totals.received,
+            total_transmitted: totals.transmitted,
+        }
+    }
+}
+
+/// Keeps the network interfaces between samples so their counters turn into rates.
+struct NetworkSampler {
+    networks: sysinfo::Networks,
+    /// Interfaces that go away are dropped, one that comes back starts over.
+    totals: HashMap<String, NetworkTotals>,
+    refreshed: Instant,
+    elapsed: Duration,
+}
+
+impl Default for NetworkSampler {
+    fn default() -> Self {
         let networks = sysinfo::Networks::new_with_refreshed_list();
+        let totals = networks
+            .iter()
+            .map(|(name, data)| (name.clone(), NetworkTotals::new(data.into())))
+            .collect();
+        Self {
+            networks,
+            totals,
+            refreshed: Instant::now(),
+            elapsed: Duration::ZERO,
+        }
+    }
+}
 
-        let mut networks: Vec<Network> = networks.into_iter().map(|x| x.into()).collect();
+impl NetworkSampler {
+    fn refresh(&mut self) {
+        self.networks.refresh_list();
+        let list = self.networks.list();
+        self.totals.retain(|name, _| list.contains_key(name));
+        self.elapsed = self.refreshed.elapsed();
+        self.refreshed = Instant::now();
+    }
 
+    fn get_vec(&mut self) -> Vec<Network> {
+        let mut networks = Vec::new();
+        for (name, data) in self.networks.iter() {
+            let totals = self
+                .totals
+                .entry(name.clone())
+                .or_insert(NetworkTotals::new(data.into()))
+                .update(data.into());
+            networks.push(Network::new(name, data, self.elapsed, totals));
+        }
         networks.sort_by(|a, b| a.name.cmp(&b.name));
-
         networks
     }
 }
 
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct CpuInfo {
     pub vendor: String,
     pub brand: String,
     pub usage: f32,
     pub cores: Vec<Core>,
+    /// In °C, where `hw_linux` can read it.
+    pub temperature: Option<f64>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Process {
     pub user: String,
+    #[serde(with = "os_string")]
     pub name: OsString,
+    #[serde(with = "pid")]
     pub pid: Pid,
+    #[serde(with = "option_pid")]
+    pub parent: Option<Pid>,
     pub memory: u64,
     pub cpu: f32,
     pub command: String,
     pub run_time: u64,
     pub total_m: u64,
+    /// Bytes read from disk per second.
+    #[serde(default)]
+    pub disk_read: f64,
+    /// Bytes written to disk per second.
+    #[serde(default)]
+    pub disk_write: f64,
 }
 
 impl Data {
-    pub fn new(sys: &mut System, users: &Users) -> Self {
+    pub fn new(
+        sys: &mut System,
+        users: &Users,
+        networks: Vec<Network>,
+        disk_io: Vec<DiskIo>,
+        sensors: Vec<Sensor>,
+        batteries: Vec<Battery>,
+        elapsed: Duration,
+    ) -> Self {
+        let rate = |x: u64| match elapsed.is_zero() {
+            true => 0.0,
+            false => x as f64 / elapsed.as_secs_f64(),
+        };
         let cpu = sys.cpus();
 
         let cpu = CpuInfo {
@@ -138,6 +422,7 @@ impl Data {
             vendor: cpu[0].vendor_id().to_string(),
             usage: sys.global_cpu_usage(),
             cores: cpu.iter().map(|x| x.into()).collect::<Vec<Core>>(),
+            temperature: hw_linux::cpu::CpuInfo::get().ok().and_then(|x| x.temp),
         };
 
         let memory = Memory {
@@ -147,11 +432,12 @@ impl Data {
             used_swap: sys.used_swap(),
         };
 
-        let mut processes = sys
+        let processes = sys
             .processes()
             .iter()
             .map(|(p, x)| Process {
                 pid: *p,
+                parent: x.parent(),
                 name: x.name().to_owned(),
                 user: if let Some(x) = x.user_id() {
                     users.get_user_by_id(x).unwrap().name().to_string()
@@ -166,19 +452,105 @@ impl Data {
                 memory: x.memory(),
                 run_time: x.run_time(),
                 total_m: memory.total_mem,
+                disk_read: rate(x.disk_usage().read_bytes),
+                disk_write: rate(x.disk_usage().written_bytes),
             })
             .collect::<Vec<Process>>();
-        processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
 
         let disks = Disk::get_vec_from_sysinfo();
-        let networks = Network::get_vec_from_sysinfo();
+        let summary = Summary::new(sys);
 
         Self {
+            timestamp: now(),
             cpu,
             processes,
             disks,
             memory,
             networks,
+            disk_io,
+            sensors,
+            batteries,
+            summary,
         }
     }
 }
+
+/// Owns the sysinfo state that is kept between samples.
+pub struct Sampler {
+    sys: System,
+    users: Users,
+    networks: NetworkSampler,
+    disk_io: DiskIoSampler,
+    sensors: SensorSampler,
+    sysfs: PathBuf,
+    topology: Topology,
+    refreshed: Instant,
+    elapsed: Duration,
+}
+
+impl Sampler {
+    /// `sysfs` is where sensors, batteries and the CPU topology are read from, normally `/sys`.
+    pub fn new(sysfs: PathBuf) -> Self {
+        Self {
+            sys: System::new_all(),
+            users: Users::new_with_refreshed_list(),
+            networks: NetworkSampler::default(),
+            disk_io: DiskIoSampler::default(),
+            sensors: SensorSampler::new(sysfs.clone()),
+            sysfs,
+            topology: Topology::default(),
+            refreshed: Instant::now(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn sample(&mut self) -> Data {
+        let networks = self.networks.get_vec();
+        let disk_io = self.disk_io.get_vec();
+        let sensors = self.sensors.get_vec();
+        let batteries = Battery::get_vec(&self.sysfs);
+        let mut data = Data::new(
+            &mut self.sys,
+            &self.users,
+            networks,
+            disk_io,
+            sensors,
+            batteries,
+            self.elapsed,
+        );
+        self.topology.apply(&self.sysfs, &mut data.cpu.cores);
+        data
+    }
+
+    pub fn refresh(&mut self) {
+        self.sys.refresh_all();
+        self.networks.refresh();
+        self.disk_io.refresh();
+        self.sensors.refresh();
+        self.elapsed = self.refreshed.elapsed();
+        self.refreshed = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(received: u64, transmitted: u64) -> NetworkCounters {
+        NetworkCounters {
+            received,
+            transmitted,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn network_totals_survive_counter_resets() {
+        let mut totals = NetworkTotals::new(counters(1000, 500));
+        assert_eq!(totals.update(counters(1000, 500)), counters(0, 0));
+        assert_eq!(totals.update(counters(1500, 700)), counters(500, 200));
+        // The interface was recreated, its counters start from 0 again.
+        assert_eq!(totals.update(counters(100, 50)), counters(600, 250));
+        assert_eq!(totals.update(counters(300, 50)), counters(800, 250));
+    }
+}
@@ -0,0 +1,115 @@
+//! Serde support for the sysinfo types stored in [`Data`](super::Data). sysinfo only
+//! implements `Serialize` for them behind a feature, these implement both so every `data`
+//! struct can derive `Serialize` and `Deserialize`.
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::{ffi::OsString, net::IpAddr};
+use sysinfo::{DiskKind, IpNetwork, MacAddr, Pid};
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "DiskKind")]
+pub enum DiskKindDef {
+    HDD,
+    SSD,
+    Unknown(isize),
+}
+
+pub mod pid {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(pid: &Pid, serializer: S) -> Result<S::Ok, S::Error> {
+        pid.as_u32().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pid, D::Error> {
+        Ok(Pid::from_u32(u32::deserialize(deserializer)?))
+    }
+}
+
+pub mod option_pid {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(pid: &Option<Pid>, serializer: S) -> Result<S::Ok, S::Error> {
+        pid.map(|x| x.as_u32()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Pid>, D::Error> {
+        Ok(Option::<u32>::deserialize(deserializer)?.map(Pid::from_u32))
+    }
+}
+
+/// Written as `aa:bb:cc:dd:ee:ff`, the same as its `Display` implementation.
+pub mod mac_addr {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(mac: &MacAddr, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&mac.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MacAddr, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let mut bytes = [0; 6];
+        let mut parts = text.split(':');
+        for byte in bytes.iter_mut() {
+            let part = parts
+                .next()
+                .ok_or(D::Error::custom("mac address is too short"))?;
+            *byte = u8::from_str_radix(part, 16).map_err(D::Error::custom)?;
+        }
+        match parts.next() {
+            Some(_) => Err(D::Error::custom("mac address is too long")),
+            None => Ok(MacAddr(bytes)),
+        }
+    }
+}
+
+pub mod ip_networks {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Network {
+        addr: IpAddr,
+        prefix: u8,
+    }
+
+    pub fn serialize<S: Serializer>(
+        networks: &[IpNetwork],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        networks
+            .iter()
+            .map(|x| Network {
+                addr: x.addr,
+                prefix: x.prefix,
+            })
+            .collect::<Vec<Network>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<IpNetwork>, D::Error> {
+        Ok(Vec::<Network>::deserialize(deserializer)?
+            .into_iter()
+            .map(|x| IpNetwork {
+                addr: x.addr,
+                prefix: x.prefix,
+            })
+            .collect())
+    }
+}
+
+/// Written as a (lossy) UTF-8 string rather than serde's platform specific byte encoding.
+pub mod os_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &OsString, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string_lossy())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<OsString, D::Error> {
+        Ok(OsString::from(String::deserialize(deserializer)?))
+    }
+}
@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use sysinfo::Components;
+
+/// The sysfs root of the running system. Anything else is treated as a copy made for testing.
+pub const SYSFS: &str = "/sys";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorKind {
+    Temperature,
+    Fan,
+    Voltage,
+}
+
+impl SensorKind {
+    pub fn unit(&self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "°C",
+            SensorKind::Fan => "RPM",
+            SensorKind::Voltage => "V",
+        }
+    }
+
+    /// The hwmon file prefix and the factor its values are divided by, hwmon reports
+    /// millidegrees, RPM and millivolts.
+    fn hwmon(&self) -> (&'static str, f64) {
+        match self {
+            SensorKind::Temperature => ("temp", 1000.0),
+            SensorKind::Fan => ("fan", 1.0),
+            SensorKind::Voltage => ("in", 1000.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Sensor {
+    /// The hwmon chip name, or `sysinfo` for components only sysinfo knows about.
+    pub chip: String,
+    pub label: String,
+    pub kind: SensorKind,
+    pub value: f64,
+    pub max: Option<f64>,
+    pub critical: Option<f64>,
+}
+
+impl Sensor {
+    /// The name used to tell sensors apart across samples.
+    pub fn name(&self) -> String {
+        format!("{} {}", self.chip, self.label)
+    }
+}
+
+fn read_line(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|x| x.trim().to_string())
+        .filter(|x| !x.is_empty())
+}
+
+fn read_value(path: &Path, divisor: f64) -> Option<f64> {
+    read_line(path)?.parse::<f64>().ok().map(|x| x / divisor)
+}
+
+/// Reads one hwmon chip directory, e.g. `/sys/class/hwmon/hwmon0`.
+fn read_chip(dir: &Path) -> Vec<Sensor> {
+    let chip = read_line(&dir.join("name")).unwrap_or_else(|| {
+        dir.file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+
+    let mut inputs = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|x| x.file_name().into_string().ok())
+        .filter_map(|x| x.strip_suffix("_input").map(|x| x.to_string()))
+        .collect::<Vec<String>>();
+    inputs.sort_by_key(|x| {
+        let number = x.trim_start_matches(|c: char| !c.is_ascii_digit());
+        (
+            x.len() - number.len(),
+            number.parse::<u32>().unwrap_or_default(),
+        )
+    });
+
+    let mut sensors = Vec::new();
+    for input in inputs {
+        let Some(kind) = [
+            SensorKind::Temperature,
+            SensorKind::Fan,
+            SensorKind::Voltage,
+        ]
+        .into_iter()
+        .find(|x| {
+            input
+                .strip_prefix(x.hwmon().0)
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        }) else {
+            continue;
+        };
+        let divisor = kind.hwmon().1;
+        let file = |suffix: &str| dir.join(format!("{}_{}", input, suffix));
+        let Some(value) = read_value(&file("input"), divisor) else {
+            continue;
+        };
+
+        sensors.push(Sensor {
+            chip: chip.clone(),
+            label: read_line(&file("label")).unwrap_or(input.clone()),
+            kind,
+            value,
+            max: read_value(&file("max"), divisor),
+            critical: read_value(&file("crit"), divisor),
+        });
+    }
+    sensors
+}
+
+/// Reads every temperature, fan and voltage sensor under `<root>/class/hwmon`.
+pub fn read_hwmon(root: &Path) -> Vec<Sensor> {
+    let mut chips = fs::read_dir(root.join("class").join("hwmon"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|x| x.path())
+        .collect::<Vec<PathBuf>>();
+    chips.sort();
+    chips.iter().flat_map(|x| read_chip(x)).collect()
+}
+
+/// Keeps the sysinfo components between samples so they only have to be listed once.
+pub struct SensorSampler {
+    root: PathBuf,
+    components: Option<Components>,
+}
+
+impl SensorSampler {
+    /// sysinfo always reads the real system, so its components are left out when `root` is
+    /// not [`SYSFS`].
+    pub fn new(root: PathBuf) -> Self {
+        let components = match root == Path::new(SYSFS) {
+            true => Some(Components::new_with_refreshed_list()),
+            false => None,
+        };
+        Self { root, components }
+    }
+
+    pub fn refresh(&mut self) {
+        if let Some(components) = self.components.as_mut() {
+            components.refresh();
+        }
+    }
+
+    /// Every hwmon sensor, followed by the sysinfo components that aren't one of them.
+    pub fn get_vec(&self) -> Vec<Sensor> {
+        let mut sensors = read_hwmon(&self.root);
+
+        for component in self.components.iter().flatten() {
+            let label = component.label().to_string();
+            // sysinfo labels its hwmon temperatures as "<chip> <label>".
+            let known = sensors
+                .iter()
+                .any(|x| x.kind == SensorKind::Temperature && label.starts_with(&x.name()));
+            if known || component.temperature().is_nan() {
+                continue;
+            }
+
+            sensors.push(Sensor {
+                chip: "sysinfo".to_string(),
+                label,
+                kind: SensorKind::Temperature,
+                value: component.temperature() as f64,
+                max: Some(component.max() as f64).filter(|x| !x.is_nan()),
+                critical: component.critical().map(|x| x as f64),
+            });
+        }
+        sensors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixture::TempDir;
+
+    #[test]
+    fn reads_hwmon_chips() {
+        let root = TempDir::new("hwmon");
+        root.write(
+            "class/hwmon/hwmon0",
+            &[
+                ("name", "coretemp"),
+                ("temp1_input", "45000"),
+                ("temp1_max", "80000"),
+                ("temp1_crit", "100000"),
+                ("temp1_label", "Package id 0"),
+                ("temp10_input", "41500"),
+                ("temp2_input", "40000"),
+                ("temp3_input", ""),
+                ("fan1_input", "1200"),
+                ("in0_input", "1250"),
+                ("pwm1_input", "128"),
+            ],
+        );
+        // A chip without a name is called after its directory.
+        root.write("class/hwmon/hwmon1", &[("temp1_input", "30000")]);
+
+        let sensor = |chip: &str, label: &str, kind, value, max, critical| Sensor {
+            chip: chip.to_string(),
+            label: label.to_string(),
+            kind,
+            value,
+            max,
+            critical,
+        };
+        let expected = vec![
+            sensor("coretemp", "in0", SensorKind::Voltage, 1.25, None, None),
+            sensor("coretemp", "fan1", SensorKind::Fan, 1200.0, None, None),
+            sensor(
+                "coretemp",
+                "Package id 0",
+                SensorKind::Temperature,
+                45.0,
+                Some(80.0),
+                Some(100.0),
+            ),
+            sensor(
+                "coretemp",
+                "temp2",
+                SensorKind::Temperature,
+                40.0,
+                None,
+                None,
+            ),
+            sensor(
+                "coretemp",
+                "temp10",
+                SensorKind::Temperature,
+                41.5,
+                None,
+                None,
+            ),
+            sensor("hwmon1", "temp1", SensorKind::Temperature, 30.0, None, None),
+        ];
+        assert_eq!(read_hwmon(root.path()), expected);
+        // sysinfo reads the real system, so a copy only has its own sensors.
+        assert_eq!(
+            SensorSampler::new(root.path().to_path_buf()).get_vec(),
+            expected
+        );
+    }
+
+    #[test]
+    fn missing_hwmon_is_empty() {
+        assert!(read_hwmon(Path::new("/nonexistent")).is_empty());
+    }
+}
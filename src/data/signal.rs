@@ -0,0 +1,83 @@
+use std::io;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, Signal, System};
+
+/// The signals offered by the signal picker, in the order they are listed.
+pub const SIGNALS: [Signal; 8] = [
+    Signal::Term,
+    Signal::Kill,
+    Signal::Hangup,
+    Signal::Interrupt,
+    Signal::Stop,
+    Signal::Continue,
+    Signal::User1,
+    Signal::User2,
+];
+
+pub fn name(signal: Signal) -> &'static str {
+    match signal {
+        Signal::Term => "SIGTERM",
+        Signal::Kill => "SIGKILL",
+        Signal::Hangup => "SIGHUP",
+        Signal::Interrupt => "SIGINT",
+        Signal::Stop => "SIGSTOP",
+        Signal::Continue => "SIGCONT",
+        Signal::User1 => "SIGUSR1",
+        Signal::User2 => "SIGUSR2",
+        _ => "UNKNOWN",
+    }
+}
+
+/// The start time and name of `pid`. A PID can be given to another process once its own
+/// exits, the pair tells them apart.
+pub fn identity(pid: Pid) -> Option<(u64, String)> {
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::new(),
+    );
+    let process = sys.process(pid)?;
+    Some((
+        process.start_time(),
+        process.name().to_string_lossy().to_string(),
+    ))
+}
+
+#[cfg(unix)]
+fn number(signal: Signal) -> Option<libc::c_int> {
+    Some(match signal {
+        Signal::Term => libc::SIGTERM,
+        Signal::Kill => libc::SIGKILL,
+        Signal::Hangup => libc::SIGHUP,
+        Signal::Interrupt => libc::SIGINT,
+        Signal::Stop => libc::SIGSTOP,
+        Signal::Continue => libc::SIGCONT,
+        Signal::User1 => libc::SIGUSR1,
+        Signal::User2 => libc::SIGUSR2,
+        _ => return None,
+    })
+}
+
+/// Sends `signal` to the single process `pid`, reporting why it failed (e.g. a permission
+/// error) where the platform tells us.
+#[cfg(unix)]
+pub fn send(pid: Pid, signal: Signal) -> io::Result<()> {
+    let number = number(signal).ok_or(io::Error::from(io::ErrorKind::Unsupported))?;
+    let pid = libc::pid_t::try_from(pid.as_u32()).map_err(|_| io::ErrorKind::InvalidInput)?;
+    match unsafe { libc::kill(pid, number) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn send(pid: Pid, signal: Signal) -> io::Result<()> {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]), true);
+    let process = sys.process(pid).ok_or(io::ErrorKind::NotFound)?;
+    match process.kill_with(signal) {
+        Some(true) => Ok(()),
+        Some(false) => Err(io::Error::other("the signal could not be sent")),
+        None => Err(io::ErrorKind::Unsupported.into()),
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::VecDeque;
+
+use crate::data::Data;
+
+/// The stretch of time each downsampled bucket covers, in milliseconds.
+pub const BUCKET: u64 = 60_000;
+
+/// The minimum, maximum and mean of a value over a bucket.
+#[derive(Clone, Copy, Debug)]
+pub struct Stat {
+    pub min: f64,
+    pub max: f64,
+    sum: f64,
+    count: u32,
+}
+
+impl Stat {
+    fn new(x: f64) -> Self {
+        Self {
+            min: x,
+            max: x,
+            sum: x,
+            count: 1,
+        }
+    }
+
+    fn add(&mut self, x: f64) {
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.sum += x;
+        self.count += 1;
+    }
+
+    pub fn avg(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+}
+
+/// The samples of one bucket reduced to the values charted over hours or days.
+#[derive(Clone, Copy, Debug)]
+pub struct Bucket {
+    /// The timestamp the bucket starts at, a multiple of `BUCKET`.
+    pub start: u64,
+    pub cpu: Stat,
+    pub memory: Stat,
+    pub swap: Stat,
+}
+
+impl Bucket {
+    fn new(data: &Data) -> Self {
+        Self {
+            start: data.timestamp - data.timestamp % BUCKET,
+            cpu: Stat::new(data.cpu.usage as f64),
+            memory: Stat::new(data.memory.mem_percent()),
+            swap: Stat::new(data.memory.swap_percent()),
+        }
+    }
+
+    fn add(&mut self, data: &Data) {
+        self.cpu.add(data.cpu.usage as f64);
+        self.memory.add(data.memory.mem_percent());
+        self.swap.add(data.memory.swap_percent());
+    }
+}
+
+/// The history behind the charts. The newest samples are kept whole, once they fall out of
+/// that window they are folded into buckets that are kept for `retention` hours.
+pub struct MetricStore {
+    samples: VecDeque<Data>,
+    capacity: usize,
+    buckets: VecDeque<Bucket>,
+    /// How long buckets are kept for in milliseconds, counted back from the newest bucket so
+    /// a gap in sampling (e.g. a suspend) doesn't keep older ones around.
+    retention: u64,
+}
+
+impl MetricStore {
+    pub fn new(capacity: usize, retention_hours: u64) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            buckets: VecDeque::new(),
+            retention: retention_hours * 3_600_000,
+        }
+    }
+
+    pub fn push(&mut self, data: Data) {
+        if self.samples.len() >= self.capacity {
+            if let Some(old) = self.samples.pop_front() {
+                self.fold(&old);
+            }
+        }
+        self.samples.push_back(data);
+    }
+
+    /// Removes the newest sample, used to step a replay back.
+    pub fn pop(&mut self) -> Option<Data> {
+        self.samples.pop_back()
+    }
+
+    fn fold(&mut self, data: &Data) {
+        if self.retention == 0 {
+            return;
+        }
+        match self.buckets.back_mut() {
+            Some(bucket) if bucket.start == data.timestamp - data.timestamp % BUCKET => {
+                bucket.add(data)
+            }
+            _ => {
+                let bucket = Bucket::new(data);
+                while self
+                    .buckets
+                    .front()
+                    .is_some_and(|x| x.start + self.retention <= bucket.start)
+                {
+                    self.buckets.pop_front();
+                }
+                self.buckets.push_back(bucket);
+            }
+        }
+    }
+
+    /// The samples kept whole, oldest first.
+    pub fn samples(&self) -> &VecDeque<Data> {
+        &self.samples
+    }
+
+    /// The buckets of samples older than `samples`, oldest first.
+    pub fn buckets(&self) -> &VecDeque<Bucket> {
+        &self.buckets
+    }
+
+    pub fn latest(&self) -> Option<&Data> {
+        self.samples.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The timestamp of the oldest thing kept, bucket or sample.
+    pub fn oldest(&self) -> Option<u64> {
+        self.buckets
+            .front()
+            .map(|x| x.start)
+            .or(self.samples.front().map(|x| x.timestamp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixture::sample;
+
+    #[test]
+    fn folds_old_samples_into_buckets() {
+        let mut store = MetricStore::new(2, 1);
+        for (i, cpu) in [10.0, 30.0, 20.0, 50.0, 0.0, 0.0].into_iter().enumerate() {
+            store.push(sample(BUCKET * 10 + i as u64 * 20_000, cpu));
+        }
+
+        // The first three samples share a bucket, the fourth starts the next one.
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.buckets().len(), 2);
+        let first = store.buckets()[0];
+        assert_eq!(first.start, BUCKET * 10);
+        assert_eq!(
+            (first.cpu.min, first.cpu.max, first.cpu.avg()),
+            (10.0, 30.0, 20.0)
+        );
+        assert_eq!(first.memory.avg(), 25.0);
+        assert_eq!(store.buckets()[1].start, BUCKET * 11);
+        assert_eq!(store.buckets()[1].cpu.avg(), 50.0);
+        assert_eq!(store.oldest(), Some(BUCKET * 10));
+    }
+
+    #[test]
+    fn drops_buckets_older_than_retention() {
+        let hour = 3_600_000;
+        let mut store = MetricStore::new(1, 1);
+        store.push(sample(0, 10.0));
+        store.push(sample(BUCKET, 20.0));
+        store.push(sample(BUCKET * 59, 30.0));
+        // Folds the sample at BUCKET * 59, both earlier buckets are within the hour.
+        store.push(sample(BUCKET * 60, 40.0));
+        assert_eq!(
+            store
+                .buckets()
+                .iter()
+                .map(|x| x.start)
+                .collect::<Vec<u64>>(),
+            [0, BUCKET, BUCKET * 59]
+        );
+
+        // Folds the sample at BUCKET * 60, an hour after the first bucket.
+        store.push(sample(hour * 5, 50.0));
+        assert_eq!(
+            store
+                .buckets()
+                .iter()
+                .map(|x| x.start)
+                .collect::<Vec<u64>>(),
+            [BUCKET, BUCKET * 59, BUCKET * 60]
+        );
+
+        // After a gap, e.g. a suspend, everything before it is too old.
+        store.push(sample(hour * 6, 60.0));
+        assert_eq!(
+            store
+                .buckets()
+                .iter()
+                .map(|x| x.start)
+                .collect::<Vec<u64>>(),
+            [hour * 5]
+        );
+        assert_eq!(store.oldest(), Some(hour * 5));
+    }
+
+    #[test]
+    fn no_retention_keeps_no_buckets() {
+        let mut store = MetricStore::new(1, 0);
+        store.push(sample(0, 10.0));
+        store.push(sample(BUCKET, 20.0));
+        assert!(store.buckets().is_empty());
+        assert_eq!(store.oldest(), Some(BUCKET));
+    }
+}
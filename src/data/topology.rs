@@ -0,0 +1,45 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::data::Core;
+
+/// Where a logical CPU sits, read from `<root>/devices/system/cpu/<cpu>`.
+#[derive(Clone, Copy, Debug, Default)]
+struct Placement {
+    socket: Option<usize>,
+    node: Option<usize>,
+}
+
+impl Placement {
+    fn read(root: &Path, cpu: &str) -> Self {
+        let dir = root.join("devices").join("system").join("cpu").join(cpu);
+        let socket = fs::read_to_string(dir.join("topology").join("physical_package_id"))
+            .ok()
+            .and_then(|x| x.trim().parse::<usize>().ok());
+        // The node is only given by a `nodeN` link in the CPU's directory.
+        let node = fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|x| x.file_name().into_string().ok())
+            .find_map(|x| x.strip_prefix("node")?.parse::<usize>().ok());
+        Self { socket, node }
+    }
+}
+
+/// The socket and NUMA node of every core. These don't change while running, so each CPU is
+/// only read once.
+#[derive(Default)]
+pub struct Topology(HashMap<String, Placement>);
+
+impl Topology {
+    pub fn apply(&mut self, root: &Path, cores: &mut [Core]) {
+        for core in cores.iter_mut() {
+            let placement = *self
+                .0
+                .entry(core.name.clone())
+                .or_insert_with(|| Placement::read(root, &core.name));
+            core.socket = placement.socket;
+            core.node = placement.node;
+        }
+    }
+}
@@ -0,0 +1,755 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{borrow::Cow, collections::BTreeMap};
+
+use crate::Page;
+
+/// Keys with a name longer than one character, as they are written in the config.
+const NAMED: [(&str, KeyCode); 14] = [
+    ("Space", KeyCode::Char(' ')),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("PgUp", KeyCode::PageUp),
+    ("PgDn", KeyCode::PageDown),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("Enter", KeyCode::Enter),
+    ("Esc", KeyCode::Esc),
+    ("Tab", KeyCode::Tab),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+];
+
+/// A key with the modifiers that matter, shift is already part of the character.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    const fn new(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    const fn char(c: char) -> Self {
+        Self::new(KeyCode::Char(c))
+    }
+
+    const fn ctrl(c: char) -> Self {
+        Self {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::CONTROL,
+        }
+    }
+
+    /// Reads a key such as `q`, `PgDn`, `F5` or `C-r`, with `C-` for control and `A-` for alt.
+    fn parse(name: &str) -> Option<Self> {
+        for (prefix, modifier) in [("C-", KeyModifiers::CONTROL), ("A-", KeyModifiers::ALT)] {
+            if let Some(rest) = name.strip_prefix(prefix).filter(|x| !x.is_empty()) {
+                let mut key = Self::parse(rest)?;
+                key.modifiers |= modifier;
+                return Some(key);
+            }
+        }
+
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Some(Self::char(c));
+        }
+        if let Some((_, code)) = NAMED.iter().find(|x| x.0 == name) {
+            return Some(Self::new(*code));
+        }
+        match name.strip_prefix('F').and_then(|x| x.parse::<u8>().ok()) {
+            Some(n @ 1..=12) => Some(Self::new(KeyCode::F(n))),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        let code = match self.code {
+            KeyCode::F(n) => format!("F{}", n),
+            code => match NAMED.iter().find(|x| x.1 == code) {
+                Some((name, _)) => name.to_string(),
+                None => match code {
+                    KeyCode::Char(c) => c.to_string(),
+                    code => format!("{:?}", code),
+                },
+            },
+        };
+        let alt = match self.modifiers.contains(KeyModifiers::ALT) {
+            true => format!("A-{}", code),
+            false => code,
+        };
+        match self.modifiers.contains(KeyModifiers::CONTROL) {
+            true => format!("C-{}", alt),
+            false => alt,
+        }
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        Self {
+            code: event.code,
+            modifiers: event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT),
+        }
+    }
+}
+
+/// Reads the keys typed one after another for a binding, split by spaces so `g g` is `g`
+/// twice. Every word has to be a key, a typo like `Enterr` isn't read as a chord.
+fn parse_keys(text: &str) -> Result<Vec<Key>, String> {
+    let keys = text
+        .split_whitespace()
+        .map(|x| {
+            Key::parse(x).ok_or(format!(
+                "has '{}' which is not a key, chords are keys split by spaces like 'g g'",
+                x
+            ))
+        })
+        .collect::<Result<Vec<Key>, String>>()?;
+    match keys.is_empty() {
+        true => Err("has an empty key".to_string()),
+        false => Ok(keys),
+    }
+}
+
+/// The name of keys typed one after another, the inverse of `parse_keys`.
+pub fn names(keys: &[Key]) -> String {
+    keys.iter()
+        .map(Key::name)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Where a binding applies. The handler tries the scopes that are active from the most
+/// specific to `Global`, so a page can reuse a key bound elsewhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    Global,
+    /// The stats and monitor pages, which split their cards over pages.
+    Cards,
+    Monitor,
+    History,
+    Processes,
+    Details,
+    Filter,
+    Signal,
+    Replay,
+    Help,
+}
+
+impl Scope {
+    /// Every scope in the order the help lists them.
+    pub const ALL: [Scope; 10] = [
+        Scope::Global,
+        Scope::Cards,
+        Scope::Monitor,
+        Scope::History,
+        Scope::Processes,
+        Scope::Details,
+        Scope::Filter,
+        Scope::Signal,
+        Scope::Replay,
+        Scope::Help,
+    ];
+
+    /// The name of the scope in the `[keys]` config.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scope::Global => "global",
+            Scope::Cards => "cards",
+            Scope::Monitor => "monitor",
+            Scope::History => "history",
+            Scope::Processes => "processes",
+            Scope::Details => "details",
+            Scope::Filter => "filter",
+            Scope::Signal => "signal",
+            Scope::Replay => "replay",
+            Scope::Help => "help",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Scope::Global => "Everywhere",
+            Scope::Cards => "Stats and monitor pages",
+            Scope::Monitor => "Monitor",
+            Scope::History => "History",
+            Scope::Processes => "Processes",
+            Scope::Details => "Process details",
+            Scope::Filter => "Process filter",
+            Scope::Signal => "Signal picker",
+            Scope::Replay => "Replay",
+            Scope::Help => "Help",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    NextPage,
+    PreviousPage,
+    /// Jumps to the page at this index of `Page::ALL`.
+    Page(usize),
+    SlowerSampling,
+    FasterSampling,
+    Help,
+    NextCards,
+    PreviousCards,
+    Up,
+    Down,
+    Top,
+    Bottom,
+    Select,
+    Back,
+    CycleMode,
+    CycleGroup,
+    ZoomIn,
+    ZoomOut,
+    PanBack,
+    PanForward,
+    Cursor,
+    Live,
+    Signal,
+    SortPrevious,
+    SortNext,
+    Reverse,
+    Filter,
+    Tree,
+    Collapse,
+    DiskColumns,
+    NextList,
+    Regex,
+    Invert,
+    Confirm,
+    Cancel,
+    Pause,
+    StepBack,
+    StepForward,
+    ReplaySlower,
+    ReplayFaster,
+}
+
+impl Action {
+    /// The name of the action in the `[keys]` config.
+    pub fn name(&self) -> String {
+        match self {
+            Action::Quit => "quit".to_string(),
+            Action::NextPage => "next_page".to_string(),
+            Action::PreviousPage => "previous_page".to_string(),
+            Action::Page(i) => format!("page_{}", i + 1),
+            Action::SlowerSampling => "slower_sampling".to_string(),
+            Action::FasterSampling => "faster_sampling".to_string(),
+            Action::Help => "help".to_string(),
+            Action::NextCards => "next_cards".to_string(),
+            Action::PreviousCards => "previous_cards".to_string(),
+            Action::Up => "up".to_string(),
+            Action::Down => "down".to_string(),
+            Action::Top => "top".to_string(),
+            Action::Bottom => "bottom".to_string(),
+            Action::Select => "select".to_string(),
+            Action::Back => "back".to_string(),
+            Action::CycleMode => "cycle_mode".to_string(),
+            Action::CycleGroup => "cycle_group".to_string(),
+            Action::ZoomIn => "zoom_in".to_string(),
+            Action::ZoomOut => "zoom_out".to_string(),
+            Action::PanBack => "pan_back".to_string(),
+            Action::PanForward => "pan_forward".to_string(),
+            Action::Cursor => "cursor".to_string(),
+            Action::Live => "live".to_string(),
+            Action::Signal => "signal".to_string(),
+            Action::SortPrevious => "sort_previous".to_string(),
+            Action::SortNext => "sort_next".to_string(),
+            Action::Reverse => "reverse".to_string(),
+            Action::Filter => "filter".to_string(),
+            Action::Tree => "tree".to_string(),
+            Action::Collapse => "collapse".to_string(),
+            Action::DiskColumns => "disk_columns".to_string(),
+            Action::NextList => "next_list".to_string(),
+            Action::Regex => "regex".to_string(),
+            Action::Invert => "invert".to_string(),
+            Action::Confirm => "confirm".to_string(),
+            Action::Cancel => "cancel".to_string(),
+            Action::Pause => "pause".to_string(),
+            Action::StepBack => "step_back".to_string(),
+            Action::StepForward => "step_forward".to_string(),
+            Action::ReplaySlower => "replay_slower".to_string(),
+            Action::ReplayFaster => "replay_faster".to_string(),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Action::Quit => "Quit".to_string(),
+            Action::NextPage => "Next page".to_string(),
+            Action::PreviousPage => "Previous page".to_string(),
+            Action::Page(i) => format!("Go to {}", Page::ALL[*i].title()),
+            Action::SlowerSampling => "Double the sampling interval".to_string(),
+            Action::FasterSampling => "Halve the sampling interval".to_string(),
+            Action::Help => "Show or hide this help".to_string(),
+            Action::NextCards => "Next page of cards".to_string(),
+            Action::PreviousCards => "Previous page of cards".to_string(),
+            Action::Up => "Move up".to_string(),
+            Action::Down => "Move down".to_string(),
+            Action::Top => "Go to the top".to_string(),
+            Action::Bottom => "Go to the bottom".to_string(),
+            Action::Select => "Select or open".to_string(),
+            Action::Back => "Go back, deselect or clear".to_string(),
+            Action::CycleMode => "Cycle the core view".to_string(),
+            Action::CycleGroup => "Cycle the core grouping".to_string(),
+            Action::ZoomIn => "Zoom in".to_string(),
+            Action::ZoomOut => "Zoom out".to_string(),
+            Action::PanBack => "Pan back or move the cursor back".to_string(),
+            Action::PanForward => "Pan forward or move the cursor forward".to_string(),
+            Action::Cursor => "Show or hide the cursor".to_string(),
+            Action::Live => "Follow the newest sample".to_string(),
+            Action::Signal => "Send a signal".to_string(),
+            Action::SortPrevious => "Sort by the previous column".to_string(),
+            Action::SortNext => "Sort by the next column".to_string(),
+            Action::Reverse => "Reverse the sort order".to_string(),
+            Action::Filter => "Filter the processes".to_string(),
+            Action::Tree => "Show or hide the process tree".to_string(),
+            Action::Collapse => "Collapse or expand the selected process".to_string(),
+            Action::DiskColumns => "Show or hide the disk columns".to_string(),
+            Action::NextList => "Switch between environment and open files".to_string(),
+            Action::Regex => "Match as a regular expression".to_string(),
+            Action::Invert => "Invert the match".to_string(),
+            Action::Confirm => "Send the signal".to_string(),
+            Action::Cancel => "Don't send the signal".to_string(),
+            Action::Pause => "Pause or resume".to_string(),
+            Action::StepBack => "Step back a sample".to_string(),
+            Action::StepForward => "Step forward a sample".to_string(),
+            Action::ReplaySlower => "Replay slower".to_string(),
+            Action::ReplayFaster => "Replay faster".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Binding {
+    pub scope: Scope,
+    /// The keys typed one after another, most bindings are a single key.
+    pub keys: Cow<'static, [Key]>,
+    pub action: Action,
+}
+
+const fn bind(scope: Scope, keys: &'static [Key], action: Action) -> Binding {
+    Binding {
+        scope,
+        keys: Cow::Borrowed(keys),
+        action,
+    }
+}
+
+/// The default key bindings. The key handler and the help both read them from the keymap
+/// built from these and the `[keys]` config.
+const BINDINGS: &[Binding] = &[
+    bind(Scope::Global, &[Key::char('q')], Action::Quit),
+    bind(Scope::Global, &[Key::new(KeyCode::Right)], Action::NextPage),
+    bind(
+        Scope::Global,
+        &[Key::new(KeyCode::Left)],
+        Action::PreviousPage,
+    ),
+    bind(Scope::Global, &[Key::char('1')], Action::Page(0)),
+    bind(Scope::Global, &[Key::char('2')], Action::Page(1)),
+    bind(Scope::Global, &[Key::char('3')], Action::Page(2)),
+    bind(Scope::Global, &[Key::char('4')], Action::Page(3)),
+    bind(Scope::Global, &[Key::char('5')], Action::Page(4)),
+    bind(Scope::Global, &[Key::char('6')], Action::Page(5)),
+    bind(Scope::Global, &[Key::char('7')], Action::Page(6)),
+    bind(Scope::Global, &[Key::char('+')], Action::SlowerSampling),
+    bind(Scope::Global, &[Key::char('-')], Action::FasterSampling),
+    bind(Scope::Global, &[Key::char('?')], Action::Help),
+    bind(
+        Scope::Cards,
+        &[Key::new(KeyCode::PageDown)],
+        Action::NextCards,
+    ),
+    bind(
+        Scope::Cards,
+        &[Key::new(KeyCode::PageUp)],
+        Action::PreviousCards,
+    ),
+    bind(Scope::Monitor, &[Key::char('c')], Action::CycleMode),
+    bind(Scope::Monitor, &[Key::char('g')], Action::CycleGroup),
+    bind(Scope::History, &[Key::char('z')], Action::ZoomIn),
+    bind(Scope::History, &[Key::char('Z')], Action::ZoomOut),
+    bind(Scope::History, &[Key::char('h')], Action::PanBack),
+    bind(Scope::History, &[Key::char('l')], Action::PanForward),
+    bind(Scope::History, &[Key::char('c')], Action::Cursor),
+    bind(Scope::History, &[Key::new(KeyCode::End)], Action::Live),
+    bind(Scope::Processes, &[Key::new(KeyCode::Up)], Action::Up),
+    bind(Scope::Processes, &[Key::new(KeyCode::Down)], Action::Down),
+    bind(
+        Scope::Processes,
+        &[Key::char('g'), Key::char('g')],
+        Action::Top,
+    ),
+    bind(Scope::Processes, &[Key::char('G')], Action::Bottom),
+    bind(
+        Scope::Processes,
+        &[Key::new(KeyCode::Enter)],
+        Action::Select,
+    ),
+    bind(Scope::Processes, &[Key::new(KeyCode::Esc)], Action::Back),
+    bind(Scope::Processes, &[Key::char('k')], Action::Signal),
+    bind(Scope::Processes, &[Key::char('<')], Action::SortPrevious),
+    bind(Scope::Processes, &[Key::char('>')], Action::SortNext),
+    bind(Scope::Processes, &[Key::char('r')], Action::Reverse),
+    bind(Scope::Processes, &[Key::char('/')], Action::Filter),
+    bind(Scope::Processes, &[Key::char('t')], Action::Tree),
+    bind(Scope::Processes, &[Key::char(' ')], Action::Collapse),
+    bind(Scope::Processes, &[Key::char('d')], Action::DiskColumns),
+    bind(Scope::Details, &[Key::new(KeyCode::Up)], Action::Up),
+    bind(Scope::Details, &[Key::new(KeyCode::Down)], Action::Down),
+    bind(Scope::Details, &[Key::new(KeyCode::Tab)], Action::NextList),
+    bind(Scope::Details, &[Key::new(KeyCode::Esc)], Action::Back),
+    bind(Scope::Details, &[Key::new(KeyCode::Enter)], Action::Back),
+    bind(Scope::Details, &[Key::char('k')], Action::Signal),
+    bind(Scope::Filter, &[Key::new(KeyCode::Enter)], Action::Select),
+    bind(Scope::Filter, &[Key::new(KeyCode::Esc)], Action::Back),
+    bind(Scope::Filter, &[Key::ctrl('r')], Action::Regex),
+    bind(Scope::Filter, &[Key::ctrl('n')], Action::Invert),
+    bind(Scope::Signal, &[Key::new(KeyCode::Up)], Action::Up),
+    bind(Scope::Signal, &[Key::new(KeyCode::Down)], Action::Down),
+    bind(Scope::Signal, &[Key::new(KeyCode::Enter)], Action::Select),
+    bind(Scope::Signal, &[Key::new(KeyCode::Esc)], Action::Back),
+    bind(Scope::Signal, &[Key::char('y')], Action::Confirm),
+    bind(Scope::Signal, &[Key::char('n')], Action::Cancel),
+    bind(Scope::Replay, &[Key::char('p')], Action::Pause),
+    bind(Scope::Replay, &[Key::char(',')], Action::StepBack),
+    bind(Scope::Replay, &[Key::char('.')], Action::StepForward),
+    bind(Scope::Replay, &[Key::char('[')], Action::ReplaySlower),
+    bind(Scope::Replay, &[Key::char(']')], Action::ReplayFaster),
+    bind(Scope::Help, &[Key::new(KeyCode::Up)], Action::Up),
+    bind(Scope::Help, &[Key::new(KeyCode::Down)], Action::Down),
+    bind(Scope::Help, &[Key::char('g'), Key::char('g')], Action::Top),
+    bind(Scope::Help, &[Key::char('G')], Action::Bottom),
+    bind(Scope::Help, &[Key::new(KeyCode::Esc)], Action::Back),
+    bind(Scope::Help, &[Key::char('?')], Action::Back),
+    bind(Scope::Help, &[Key::char('q')], Action::Back),
+];
+
+/// Scopes that are active at the same time, along with `Global` which a page may shadow on
+/// purpose other than its `KEPT` actions. Keys can't clash between any two scopes of a
+/// group, the first would hide the other.
+const TOGETHER: &[&[Scope]] = &[
+    &[Scope::Replay, Scope::Monitor, Scope::Cards],
+    &[Scope::Replay, Scope::History],
+    &[Scope::Replay, Scope::Processes],
+    &[Scope::Replay, Scope::Details],
+];
+
+/// The global actions no page may shadow, so there's always a way out and to the help.
+const KEPT: [Action; 2] = [Action::Quit, Action::Help];
+
+/// Whether `a` and `b` can be typed on the same page, so one would hide the other.
+fn together(a: &Binding, b: &Binding) -> bool {
+    let hides_kept = |page: &Binding, global: &Binding| {
+        global.scope == Scope::Global
+            && KEPT.contains(&global.action)
+            && TOGETHER.iter().any(|x| x.contains(&page.scope))
+    };
+    a.scope == b.scope
+        || TOGETHER
+            .iter()
+            .any(|x| x.contains(&a.scope) && x.contains(&b.scope))
+        || hides_kept(a, b)
+        || hides_kept(b, a)
+}
+
+/// What the keys typed so far do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Match {
+    Action(Action),
+    /// The keys are the start of a longer binding, wait for the next one.
+    Pending,
+    Unbound,
+}
+
+#[derive(Debug)]
+pub struct Keymap(Vec<Binding>);
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self(BINDINGS.to_vec())
+    }
+}
+
+impl Keymap {
+    /// The default bindings with the actions in `keys` rebound, e.g. `keys.processes.signal =
+    /// ["x"]`. An action can only be bound in the scopes it has by default. Fails if a
+    /// binding is unknown, or if two bindings in scopes active together share keys or one
+    /// starts with the keys of another, which could never be typed.
+    pub fn new(keys: &BTreeMap<String, BTreeMap<String, Vec<String>>>) -> Result<Self, String> {
+        let mut bindings = BINDINGS.to_vec();
+        for (scope_name, actions) in keys {
+            let scope = Scope::ALL
+                .into_iter()
+                .find(|x| x.name() == scope_name)
+                .ok_or(format!(
+                    "keys.{} is not a scope, expected one of: {}",
+                    scope_name,
+                    Scope::ALL.map(|x| x.name()).join(", ")
+                ))?;
+            for (action_name, names) in actions {
+                let mut allowed: Vec<Action> = Vec::new();
+                for binding in BINDINGS.iter().filter(|x| x.scope == scope) {
+                    if !allowed.contains(&binding.action) {
+                        allowed.push(binding.action);
+                    }
+                }
+                let action = allowed
+                    .iter()
+                    .find(|x| x.name() == *action_name)
+                    .copied()
+                    .ok_or(format!(
+                        "keys.{}.{} is not an action, expected one of: {}",
+                        scope_name,
+                        action_name,
+                        allowed
+                            .iter()
+                            .map(Action::name)
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ))?;
+
+                bindings.retain(|x| x.scope != scope || x.action != action);
+                for name in names {
+                    let keys = parse_keys(name)
+                        .map_err(|e| format!("keys.{}.{} {}", scope_name, action_name, e))?;
+                    if !bindings
+                        .iter()
+                        .any(|x| x.scope == scope && x.action == action && *x.keys == keys)
+                    {
+                        bindings.push(Binding {
+                            scope,
+                            keys: Cow::Owned(keys),
+                            action,
+                        });
+                    }
+                }
+            }
+        }
+
+        for (i, a) in bindings.iter().enumerate() {
+            for b in bindings[i + 1..].iter().filter(|x| together(x, a)) {
+                let (short, long) = match a.keys.len() <= b.keys.len() {
+                    true => (a, b),
+                    false => (b, a),
+                };
+                if !long.keys.starts_with(&short.keys) {
+                    continue;
+                }
+                return Err(match short.keys.len() == long.keys.len() {
+                    true => format!(
+                        "keys: '{}' is bound to both {}.{} and {}.{}",
+                        names(&short.keys),
+                        short.scope.name(),
+                        short.action.name(),
+                        long.scope.name(),
+                        long.action.name()
+                    ),
+                    false => format!(
+                        "keys: '{}' for {}.{} can't be typed, '{}' for {}.{} starts with it",
+                        names(&long.keys),
+                        long.scope.name(),
+                        long.action.name(),
+                        names(&short.keys),
+                        short.scope.name(),
+                        short.action.name()
+                    ),
+                });
+            }
+        }
+        Ok(Self(bindings))
+    }
+
+    /// What `keys` do in the first of `scopes` that binds them or a chord starting with them.
+    pub fn action(&self, scopes: &[Scope], keys: &[Key]) -> Match {
+        for scope in scopes {
+            let mut pending = false;
+            for binding in self.0.iter().filter(|x| x.scope == *scope) {
+                if *binding.keys == *keys {
+                    return Match::Action(binding.action);
+                }
+                pending |= binding.keys.starts_with(keys);
+            }
+            if pending {
+                return Match::Pending;
+            }
+        }
+        Match::Unbound
+    }
+
+    /// The keys bound to `action` in `scope` for the instructions, e.g. `Esc/?`.
+    pub fn hint(&self, scope: Scope, action: Action) -> String {
+        self.0
+            .iter()
+            .filter(|x| x.scope == scope && x.action == action)
+            .map(|x| names(&x.keys))
+            .collect::<Vec<String>>()
+            .join("/")
+    }
+
+    /// Hints for the instructions, e.g. `Sort <</>> | Filter </>`, with the keys of the
+    /// actions of each label joined. Labels with none of their actions bound are left out.
+    pub fn hints(&self, scope: Scope, hints: &[(&str, &[Action])]) -> String {
+        hints
+            .iter()
+            .filter_map(|(label, actions)| {
+                let keys = actions
+                    .iter()
+                    .map(|x| self.hint(scope, *x))
+                    .filter(|x| !x.is_empty())
+                    .collect::<Vec<String>>();
+                (!keys.is_empty()).then(|| format!("{} <{}>", label, keys.join("/")))
+            })
+            .collect::<Vec<String>>()
+            .join(" | ")
+    }
+
+    /// Every binding of `scope`, with the keys of an action joined together.
+    pub fn list(&self, scope: Scope) -> Vec<(String, String)> {
+        let mut list: Vec<(Action, Vec<String>)> = Vec::new();
+        for binding in self.0.iter().filter(|x| x.scope == scope) {
+            match list.iter_mut().find(|x| x.0 == binding.action) {
+                Some((_, keys)) => keys.push(names(&binding.keys)),
+                None => list.push((binding.action, vec![names(&binding.keys)])),
+            }
+        }
+        list.into_iter()
+            .map(|(action, keys)| (keys.join(", "), action.description()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keymap(config: &[(&str, &str, &[&str])]) -> Result<Keymap, String> {
+        let mut keys: BTreeMap<String, BTreeMap<String, Vec<String>>> = BTreeMap::new();
+        for (scope, action, names) in config {
+            keys.entry(scope.to_string()).or_default().insert(
+                action.to_string(),
+                names.iter().map(|x| x.to_string()).collect(),
+            );
+        }
+        Keymap::new(&keys)
+    }
+
+    #[test]
+    fn chords_wait_for_the_next_key() {
+        let keymap = Keymap::default();
+        let scopes = [Scope::Processes, Scope::Global];
+        let (g, j) = (Key::char('g'), Key::char('j'));
+        assert_eq!(keymap.action(&scopes, &[g]), Match::Pending);
+        assert_eq!(keymap.action(&scopes, &[g, g]), Match::Action(Action::Top));
+        assert_eq!(keymap.action(&scopes, &[g, j]), Match::Unbound);
+        assert_eq!(
+            keymap.action(&scopes, &[Key::char('q')]),
+            Match::Action(Action::Quit)
+        );
+        // The monitor binds g on its own, so it never waits there.
+        assert_eq!(
+            keymap.action(&[Scope::Monitor, Scope::Global], &[g]),
+            Match::Action(Action::CycleGroup)
+        );
+    }
+
+    #[test]
+    fn rebinds_actions() {
+        let keymap = keymap(&[
+            ("processes", "signal", &["x"]),
+            ("processes", "top", &["Home", "g g"]),
+            ("filter", "regex", &["A-r"]),
+        ])
+        .unwrap();
+        let scopes = [Scope::Processes, Scope::Global];
+        assert_eq!(
+            keymap.action(&scopes, &[Key::char('x')]),
+            Match::Action(Action::Signal)
+        );
+        assert_eq!(keymap.action(&scopes, &[Key::char('k')]), Match::Unbound);
+        assert_eq!(keymap.hint(Scope::Processes, Action::Top), "Home/g g");
+        assert_eq!(keymap.hint(Scope::Filter, Action::Regex), "A-r");
+    }
+
+    #[test]
+    fn rejects_clashing_keys() {
+        let err = keymap(&[("processes", "signal", &["t"])]).unwrap_err();
+        assert!(err.contains("'t' is bound to both"), "{}", err);
+
+        let err = keymap(&[("processes", "signal", &["g"])]).unwrap_err();
+        assert!(
+            err.contains("'g g' for processes.top can't be typed"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn rejects_keys_clashing_across_scopes() {
+        let err = keymap(&[("processes", "tree", &["p"])]).unwrap_err();
+        assert!(err.contains("replay.pause"), "{}", err);
+
+        let err = keymap(&[("monitor", "cycle_mode", &["PgDn"])]).unwrap_err();
+        assert!(err.contains("cards.next_cards"), "{}", err);
+
+        let err = keymap(&[("replay", "pause", &["d p"])]).unwrap_err();
+        assert!(err.contains("processes.disk_columns"), "{}", err);
+
+        // Pages are free to shadow most global keys, and unrelated pages to share them.
+        assert!(keymap(&[("history", "zoom_in", &["1"])]).is_ok());
+        assert!(keymap(&[("history", "zoom_in", &["t"])]).is_ok());
+        // The help overlay and filter aren't pages, they take every key.
+        assert!(keymap(&[("filter", "regex", &["q"])]).is_ok());
+    }
+
+    #[test]
+    fn rejects_keys_hiding_quit_and_help() {
+        let err = keymap(&[("processes", "top", &["q q"])]).unwrap_err();
+        assert!(err.contains("global.quit"), "{}", err);
+
+        let err = keymap(&[("processes", "signal", &["?"])]).unwrap_err();
+        assert!(err.contains("global.help"), "{}", err);
+
+        let err = keymap(&[("global", "quit", &["c"])]).unwrap_err();
+        assert!(err.contains("monitor.cycle_mode"), "{}", err);
+    }
+
+    #[test]
+    fn parses_key_names() {
+        assert_eq!(parse_keys("g g"), Ok(vec![Key::char('g'), Key::char('g')]));
+        assert_eq!(parse_keys("C-r"), Ok(vec![Key::ctrl('r')]));
+        assert_eq!(parse_keys("F12"), Ok(vec![Key::new(KeyCode::F(12))]));
+        assert_eq!(
+            parse_keys("PgDn Space"),
+            Ok(vec![Key::new(KeyCode::PageDown), Key::char(' ')])
+        );
+        for name in ["gg", "Enterr", "F13", "Ctrl-r", "PgDown", "C-", " "] {
+            assert!(parse_keys(name).is_err(), "{}", name);
+        }
+
+        let keys = parse_keys("C-A-x Enter g").unwrap();
+        assert_eq!(names(&keys), "C-A-x Enter g");
+        assert_eq!(parse_keys(&names(&keys)), Ok(keys));
+    }
+
+    #[test]
+    fn rejects_unknown_bindings() {
+        let err = keymap(&[("processes", "top", &["Enterr"])]).unwrap_err();
+        assert!(err.contains("'Enterr' which is not a key"), "{}", err);
+        assert!(keymap(&[("process", "top", &["g"])]).is_err());
+        assert!(keymap(&[("monitor", "top", &["g"])]).is_err());
+    }
+}
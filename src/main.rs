@@ -1,69 +1,304 @@
+pub mod alerts;
+pub mod args;
+pub mod config;
 pub mod data;
+pub mod keymap;
 pub mod pages;
+pub mod session;
+pub mod snapshot;
+pub mod theme;
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
-use hw_linux::{environment::packages::PackageManagers, InfoTrait};
+use crossterm::{
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
+    execute,
+};
 use ratatui::{
-    layout::{Constraint, Layout},
-    widgets::{Block, Borders, TableState},
+    layout::{Constraint, Layout, Position, Rect},
+    text::Line,
+    widgets::{Block, Borders, TableState, Tabs},
     DefaultTerminal, Frame,
 };
 use std::{
-    io,
-    sync::mpsc::{self, Receiver},
+    env, io, panic, process,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
     thread,
     time::Duration,
 };
-use sysinfo::{System, Users};
 
-use crate::data::Data;
+use crate::{
+    alerts::Alerts,
+    args::{Args, Command, HELP, MIN_INTERVAL},
+    config::Config,
+    data::{host::Host, store::MetricStore, Data, Sampler},
+    keymap::{Action, Key, Keymap, Match, Scope},
+    pages::{
+        details::DetailView,
+        history::HistoryView,
+        monitor::MonitorView,
+        processes::{ProcessFilter, ProcessRow, ProcessSort, ProcessTree, Selected},
+        signal::{SignalPicker, Stage},
+    },
+    session::{Recorder, Replay},
+    theme::Theme,
+};
 
-const WAIT: Duration = Duration::from_millis(1000);
+const MAX_INTERVAL: Duration = Duration::from_secs(60);
+/// The tabs start after the "Monitors" title.
+const TABS_OFFSET: u16 = 9;
+/// Rows the process selection moves per step of the mouse wheel.
+const SCROLL: isize = 3;
 
 fn main() -> io::Result<()> {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, HELP);
+            process::exit(2);
+        }
+    };
+    match args.command {
+        Command::Help => {
+            println!("{}", HELP);
+            return Ok(());
+        }
+        Command::Version => {
+            println!("monitors {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        Command::DumpDefaultConfig => {
+            print!("{}", Config::dump_default());
+            return Ok(());
+        }
+        Command::Run | Command::Json | Command::JsonLines => {}
+    }
+
+    let config = match Config::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(2);
+        }
+    };
+    let theme = match Theme::resolve(&config, args.theme.as_deref()) {
+        Ok(theme) => theme,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(2);
+        }
+    };
+    let keymap = match Keymap::new(&config.keys) {
+        Ok(keymap) => keymap,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(2);
+        }
+    };
+    let page_name = args.page.unwrap_or(config.page.clone());
+    let Some(page) = Page::from_name(&page_name) else {
+        eprintln!("error: unknown page '{}'\n\n{}", page_name, HELP);
+        process::exit(2);
+    };
+    let interval = args
+        .interval
+        .unwrap_or(Duration::from_millis(config.interval))
+        .clamp(MIN_INTERVAL, MAX_INTERVAL);
+    let history_len = args.history.unwrap_or(config.history);
+    let sysfs = args.sysfs.unwrap_or(config.sysfs.clone());
+
+    match args.command {
+        Command::Json => {
+            return snapshot::print(false, Some(args.count.unwrap_or(1)), interval, sysfs)
+        }
+        Command::JsonLines => return snapshot::print(true, args.count, interval, sysfs),
+        _ => {}
+    }
+
+    let mouse = config.mouse;
+    let tree = ProcessTree {
+        enabled: config.processes.tree,
+        ..Default::default()
+    };
+    config::set(config);
+    theme::set(theme);
+
+    let interval = Arc::new(AtomicU64::new(interval.as_millis() as u64));
     let (dtx, drx) = mpsc::channel();
-    thread::spawn(move || {
-        let mut sys = System::new_all();
-        let user = Users::new_with_refreshed_list();
-        loop {
-            let data = Data::new(&mut sys, &user);
-            let _ = dtx.send(data);
-            thread::sleep(WAIT);
-            sys.refresh_all();
-        }
-    });
+    let record_error = Arc::new(Mutex::new(None));
+    let (replay, host) = match &args.replay {
+        Some(path) => {
+            let recording = session::load(path).unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                process::exit(2);
+            });
+            let replay = Replay::start(recording.samples, interval.clone(), dtx);
+            (Some(replay), recording.host)
+        }
+        None => {
+            let host = Host::get();
+            let mut recorder = args.record.as_deref().map(|path| {
+                Recorder::open(path, &host).unwrap_or_else(|e| {
+                    eprintln!("error: {}", e);
+                    process::exit(2);
+                })
+            });
+            let wait = interval.clone();
+            let error = record_error.clone();
+            thread::spawn(move || {
+                let mut sampler = Sampler::new(sysfs);
+                loop {
+                    let data = sampler.sample();
+                    // Recording stops at the first failure, the error stays on screen.
+                    if let Some(Err(e)) = recorder.as_mut().map(|x| x.write(&data)) {
+                        recorder = None;
+                        if let Ok(mut error) = error.lock() {
+                            *error = Some(e);
+                        }
+                    }
+                    let _ = dtx.send(data);
+                    thread::sleep(Duration::from_millis(wait.load(Ordering::Relaxed)));
+                    sampler.refresh();
+                }
+            });
+            (None, host)
+        }
+    };
     let mut terminal = ratatui::init();
-    let pms = PackageManagers::get().unwrap();
+    if mouse {
+        // Turned off before ratatui's own hook restores the terminal, so a panic doesn't leave
+        // the shell receiving mouse events. Failing to capture the mouse only loses clicks.
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = execute!(io::stdout(), DisableMouseCapture);
+            hook(info);
+        }));
+        let _ = execute!(io::stdout(), EnableMouseCapture);
+    }
     let table = TableState::default();
     let app_result = App {
         exit: false,
-        page: Page::Stats1,
-        history: Vec::new(),
+        page,
+        history: MetricStore::new(history_len, config::get().retention),
+        alerts: Alerts::new(replay.is_none()),
+        interval,
         table,
+        sort: ProcessSort::default(),
+        filter: ProcessFilter::default(),
+        tree,
+        signal: None,
+        selected: None,
+        details: None,
+        replay,
+        record_error,
+        monitor: MonitorView::default(),
+        history_view: HistoryView::default(),
+        card_page: 0,
+        card_pages: 1,
+        tabs: Rect::default(),
+        process_table: Rect::default(),
+        charts: Vec::new(),
+        keymap,
+        pending: Vec::new(),
+        help: None,
         drx,
-        pms,
+        host,
     }
     .run(&mut terminal);
     ratatui::restore();
+    if mouse {
+        let _ = execute!(io::stdout(), DisableMouseCapture);
+    }
     app_result
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum Page {
     Stats1,
     Stats2,
     Monitor,
+    Sensors,
+    Alerts,
     Processes,
     History,
 }
 
+impl Page {
+    /// Every page in the order they are cycled through.
+    const ALL: [Page; 7] = [
+        Page::Stats1,
+        Page::Stats2,
+        Page::Monitor,
+        Page::Sensors,
+        Page::Alerts,
+        Page::History,
+        Page::Processes,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Page::Stats1 => "Stats 1",
+            Page::Stats2 => "Stats 2",
+            Page::Monitor => "Monitor",
+            Page::Sensors => "Sensors",
+            Page::Alerts => "Alerts",
+            Page::History => "History",
+            Page::Processes => "Processes",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "stats1" => Some(Page::Stats1),
+            "stats2" => Some(Page::Stats2),
+            "monitor" => Some(Page::Monitor),
+            "sensors" => Some(Page::Sensors),
+            "alerts" => Some(Page::Alerts),
+            "processes" => Some(Page::Processes),
+            "history" => Some(Page::History),
+            _ => None,
+        }
+    }
+}
+
 pub struct App {
     exit: bool,
     page: Page,
     drx: Receiver<Data>,
-    history: Vec<Data>,
-    pms: PackageManagers,
+    history: MetricStore,
+    alerts: Alerts,
+    interval: Arc<AtomicU64>,
+    /// The machine the samples are from, the recorded one while replaying.
+    host: Host,
     table: TableState,
+    sort: ProcessSort,
+    filter: ProcessFilter,
+    tree: ProcessTree,
+    signal: Option<SignalPicker>,
+    selected: Option<Selected>,
+    details: Option<DetailView>,
+    replay: Option<Replay>,
+    /// Why recording stopped, set by the sampler thread.
+    record_error: Arc<Mutex<Option<String>>>,
+    monitor: MonitorView,
+    history_view: HistoryView,
+    /// The page of cards shown on the stats and monitor pages, and how many there are.
+    card_page: usize,
+    card_pages: usize,
+    /// Where the tabs, process table and history charts were last drawn, for the mouse.
+    tabs: Rect,
+    process_table: Rect,
+    charts: Vec<Rect>,
+    keymap: Keymap,
+    /// Keys typed so far of a binding that takes more than one.
+    pending: Vec<Key>,
+    /// How far the help overlay is scrolled, if it is open.
+    help: Option<u16>,
 }
 
 impl App {
@@ -86,31 +321,251 @@ impl App {
             Block::new().borders(Borders::TOP).title("Monitors"),
             title_bar,
         );
+        self.tabs = Rect {
+            x: title_bar.x + TABS_OFFSET,
+            width: title_bar.width.saturating_sub(TABS_OFFSET),
+            ..title_bar
+        };
+        frame.render_widget(
+            Tabs::new(Page::ALL.map(|x| x.title()))
+                .select(Page::ALL.iter().position(|x| *x == self.page))
+                .highlight_style(theme::get().highlight_style()),
+            self.tabs,
+        );
+
+        let mut ins_txt = format!(
+            " {} {}ms",
+            self.keymap.hints(
+                Scope::Global,
+                &[
+                    ("←", &[Action::PreviousPage]),
+                    ("→", &[Action::NextPage]),
+                    ("Quit", &[Action::Quit]),
+                    ("Help", &[Action::Help]),
+                    (
+                        "Interval",
+                        &[Action::SlowerSampling, Action::FasterSampling]
+                    ),
+                ]
+            ),
+            self.interval.load(Ordering::Relaxed)
+        );
 
-        let mut ins_txt = " ← <Left> | → <Right> | Quit <q>".to_string();
+        // Samples can arrive faster than we draw (e.g. a fast replay), only the newest is drawn.
+        let mut received = self.drx.try_iter().collect::<Vec<Data>>();
+        for data in received.iter() {
+            self.alerts.check(data);
+        }
+        let latest = received.pop();
+        for data in received {
+            self.history.push(data);
+        }
+        if let (Some(_), Some(view)) = (&latest, &mut self.details) {
+            view.refresh();
+        }
 
-        let mut draw = |data: &Data, history: &[Data]| match self.page {
-            Page::Stats1 => pages::stats::draw_page_1(frame, main_area, &data, &self.pms),
-            Page::Stats2 => pages::stats::draw_page_2(frame, main_area, &data),
-            Page::Monitor => pages::monitor::draw(frame, main_area, &data),
-            Page::Processes => {
-                pages::processes::draw(frame, main_area, &mut self.table, &data.processes);
-                ins_txt.push_str(" | ↑ <Up> | ↓ <Down> | Kill <k> | DeSelect <Esc>")
+        let [summary_area, page_area] = Layout::vertical([
+            Constraint::Length(pages::summary::HEIGHT),
+            Constraint::Min(0),
+        ])
+        .areas(main_area);
+
+        let mut draw = |data: &Data, history: &MetricStore| {
+            match self.page {
+                Page::Stats1 => {
+                    self.card_pages = pages::stats::draw_page_1(
+                        frame,
+                        main_area,
+                        data,
+                        &self.host,
+                        self.card_page,
+                    )
+                }
+                Page::Stats2 => {
+                    self.card_pages = pages::stats::draw_page_2(
+                        frame,
+                        main_area,
+                        data,
+                        &self.host,
+                        &self.alerts,
+                        self.card_page,
+                    )
+                }
+                Page::Monitor => {
+                    pages::summary::draw(frame, summary_area, &data.summary);
+                    self.card_pages = pages::monitor::draw(
+                        frame,
+                        page_area,
+                        data,
+                        &self.alerts,
+                        &self.monitor,
+                        self.card_page,
+                    );
+                    ins_txt.push_str(&self.monitor.status(&self.keymap));
+                }
+                Page::Sensors => {
+                    pages::sensors::draw(frame, main_area, &data.sensors, &self.alerts)
+                }
+                Page::Alerts => pages::alerts::draw(frame, main_area, &self.alerts),
+                Page::Processes if self.details.is_some() => {
+                    pages::summary::draw(frame, summary_area, &data.summary);
+                    if let Some(view) = &self.details {
+                        pages::details::draw(frame, page_area, view, history, &self.history_view);
+                    }
+                    ins_txt.push_str(" | ");
+                    ins_txt.push_str(&self.keymap.hints(
+                        Scope::Details,
+                        &[
+                            ("↑", &[Action::Up]),
+                            ("↓", &[Action::Down]),
+                            ("List", &[Action::NextList]),
+                            ("Signal", &[Action::Signal]),
+                            ("Close", &[Action::Back]),
+                        ],
+                    ))
+                }
+                Page::Processes => {
+                    let processes = pages::processes::visible(
+                        &data.processes,
+                        &self.sort,
+                        &self.filter,
+                        &self.tree,
+                    );
+                    let row = self
+                        .selected
+                        .as_mut()
+                        .and_then(|x| x.resolve(&data.processes, &processes));
+                    self.table.select(row);
+                    pages::summary::draw(frame, summary_area, &data.summary);
+                    self.process_table = page_area;
+                    pages::processes::draw(
+                        frame,
+                        page_area,
+                        &mut self.table,
+                        &processes,
+                        &self.sort,
+                    );
+                    let hints = match self.filter.editing {
+                        true => self.keymap.hints(
+                            Scope::Filter,
+                            &[
+                                ("Done", &[Action::Select]),
+                                ("Clear", &[Action::Back]),
+                                ("Regex", &[Action::Regex]),
+                                ("Invert", &[Action::Invert]),
+                            ],
+                        ),
+                        false => {
+                            let mut hints: Vec<(&str, &[Action])> = vec![
+                                ("↑", &[Action::Up]),
+                                ("↓", &[Action::Down]),
+                                ("Signal", &[Action::Signal]),
+                                ("Details", &[Action::Select]),
+                                ("DeSelect", &[Action::Back]),
+                                ("Sort", &[Action::SortPrevious, Action::SortNext]),
+                                ("Reverse", &[Action::Reverse]),
+                                ("Filter", &[Action::Filter]),
+                                ("Tree", &[Action::Tree]),
+                                ("Disk", &[Action::DiskColumns]),
+                            ];
+                            if self.replay.is_some() {
+                                hints.retain(|x| {
+                                    !matches!(x.1, [Action::Signal] | [Action::Select])
+                                });
+                            }
+                            self.keymap.hints(Scope::Processes, &hints)
+                        }
+                    };
+                    ins_txt.push_str(" | ");
+                    ins_txt.push_str(&hints);
+                    if self.tree.enabled && !self.filter.editing {
+                        ins_txt.push_str(" | ");
+                        ins_txt.push_str(
+                            &self
+                                .keymap
+                                .hints(Scope::Processes, &[("Collapse", &[Action::Collapse])]),
+                        );
+                    }
+                    if self.filter.is_active() || self.filter.editing {
+                        ins_txt.push_str(&self.filter.status(processes.len(), data.processes.len()))
+                    }
+                    if let Some(selected) = &self.selected {
+                        ins_txt.push_str(&selected.status())
+                    }
+                }
+                Page::History => {
+                    self.charts =
+                        pages::history::draw(frame, main_area, history, &self.history_view);
+                    ins_txt.push_str(&self.history_view.status(&self.keymap));
+                }
+            }
+
+            if matches!(self.page, Page::Stats1 | Page::Stats2 | Page::Monitor) {
+                self.card_page = self.card_page.min(self.card_pages - 1);
+                ins_txt.push_str(" | ");
+                ins_txt.push_str(&self.keymap.hints(
+                    Scope::Cards,
+                    &[("Page", &[Action::PreviousCards, Action::NextCards])],
+                ));
+                frame.render_widget(
+                    Block::new().title(
+                        Line::from(format!("page {}/{} ", self.card_page + 1, self.card_pages))
+                            .right_aligned(),
+                    ),
+                    title_bar,
+                );
             }
-            Page::History => pages::history::draw(frame, main_area, &history),
         };
 
-        if let Ok(data) = self.drx.try_recv() {
+        if let Some(data) = latest {
             draw(&data, &self.history);
-
-            if self.history.len() > 99 {
-                self.history.remove(0);
-            }
             self.history.push(data);
-        } else if let Some(data) = self.history.last().map(|x| x.clone()) {
+        } else if let Some(data) = self.history.latest().cloned() {
             draw(&data, &self.history);
         }
 
+        if let Some(replay) = &self.replay {
+            ins_txt.push_str(&replay.status(&self.keymap));
+        }
+        if let Some(e) = self.record_error.lock().ok().and_then(|x| x.clone()) {
+            ins_txt.push_str(&format!(" | Recording stopped: {}", e));
+        }
+
+        if let Some(scroll) = self.help {
+            self.help = Some(pages::help::draw(frame, main_area, &self.keymap, scroll));
+            ins_txt = format!(
+                " {}",
+                self.keymap.hints(
+                    Scope::Help,
+                    &[
+                        ("↑", &[Action::Up]),
+                        ("↓", &[Action::Down]),
+                        ("Close", &[Action::Back]),
+                    ]
+                )
+            );
+        }
+
+        if let Some(picker) = &self.signal {
+            let hints: &[(&str, &[Action])] = match picker.stage {
+                Stage::Pick => &[
+                    ("↑", &[Action::Up]),
+                    ("↓", &[Action::Down]),
+                    ("Select", &[Action::Select]),
+                    ("Cancel", &[Action::Back]),
+                ],
+                Stage::Confirm => &[("Yes", &[Action::Confirm]), ("No", &[Action::Cancel])],
+                Stage::Done(_) => &[("Close", &[Action::Back])],
+            };
+            let hints = self.keymap.hints(Scope::Signal, hints);
+            pages::signal::draw(frame, main_area, picker, &hints);
+            ins_txt = format!(" {}", hints);
+        }
+
+        if !self.pending.is_empty() {
+            ins_txt.push_str(&format!(" | {}…", keymap::names(&self.pending)));
+        }
+
         frame.render_widget(
             Block::new().borders(Borders::TOP).title(ins_txt),
             instruction,
@@ -118,12 +573,17 @@ impl App {
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
-        if let Ok(ev) = event::poll(WAIT) {
+        let mut wait = Duration::from_millis(self.interval.load(Ordering::Relaxed));
+        if let Some(replay) = &self.replay {
+            wait = wait.div_f64(replay.speed);
+        }
+        if let Ok(ev) = event::poll(wait) {
             if ev {
                 match event::read()? {
                     Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                         self.handle_key_event(key_event)
                     }
+                    Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
                     _ => {}
                 };
             }
@@ -132,63 +592,369 @@ impl App {
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('q') => self.exit(),
-            KeyCode::Left => self.previous(),
-            KeyCode::Right => self.next(),
-            KeyCode::Up | KeyCode::Down | KeyCode::Char('k') | KeyCode::Esc => {
-                if &self.page == &Page::Processes {
-                    match self.table.selected() {
-                        Some(i) => match key_event.code {
-                            KeyCode::Up => self.table.select_previous(),
-                            KeyCode::Down => self.table.select_next(),
-                            KeyCode::Char('k') => {
-                                if let Some(dp) = &self.history.last() {
-                                    let sys = System::new_all();
-                                    sys.processes_by_exact_name(&dp.processes[i].name)
-                                        .into_iter()
-                                        .for_each(|x| {
-                                            x.kill();
-                                        });
-                                    self.table.select(None);
-                                }
-                            }
-                            KeyCode::Esc => self.table.select(None),
-                            _ => {}
-                        },
-                        None => self.table.select_first(),
-                    };
+        self.pending.push(Key::from(key_event));
+        match self.keymap.action(&self.scopes(), &self.pending) {
+            Match::Action(action) => {
+                self.pending.clear();
+                self.perform(action);
+            }
+            Match::Pending => {}
+            // The key doesn't finish the chord, so it may start something else.
+            Match::Unbound if self.pending.len() > 1 => {
+                self.pending.clear();
+                self.handle_key_event(key_event);
+            }
+            Match::Unbound => {
+                self.pending.clear();
+                // Anything else typed into the filter is part of the query.
+                if self.filter.editing {
+                    match key_event.code {
+                        KeyCode::Char(c) => self.filter.query.push(c),
+                        KeyCode::Backspace => {
+                            self.filter.query.pop();
+                        }
+                        _ => {}
+                    }
                 }
             }
+        }
+    }
+
+    /// The scopes of the key bindings that apply right now, most specific first.
+    fn scopes(&self) -> Vec<Scope> {
+        if self.signal.is_some() {
+            return vec![Scope::Signal];
+        }
+        if self.filter.editing {
+            return vec![Scope::Filter];
+        }
+        if self.help.is_some() {
+            return vec![Scope::Help, Scope::Global];
+        }
+
+        let mut scopes = Vec::new();
+        if self.replay.is_some() {
+            scopes.push(Scope::Replay);
+        }
+        match self.page {
+            Page::Stats1 | Page::Stats2 => scopes.push(Scope::Cards),
+            Page::Monitor => scopes.extend([Scope::Monitor, Scope::Cards]),
+            Page::History => scopes.push(Scope::History),
+            Page::Processes if self.details.is_some() => scopes.push(Scope::Details),
+            Page::Processes => scopes.push(Scope::Processes),
+            Page::Sensors | Page::Alerts => {}
+        }
+        scopes.push(Scope::Global);
+        scopes
+    }
+
+    fn perform(&mut self, action: Action) {
+        if let Some(picker) = &mut self.signal {
+            match (&picker.stage, action) {
+                (Stage::Pick, Action::Up) => picker.previous(),
+                (Stage::Pick, Action::Down) => picker.next(),
+                (Stage::Pick, Action::Select) => picker.stage = Stage::Confirm,
+                (Stage::Confirm, Action::Confirm) => picker.send(),
+                (Stage::Confirm, Action::Cancel) => picker.stage = Stage::Pick,
+                (Stage::Done(_), Action::Select) | (_, Action::Back) => self.signal = None,
+                _ => {}
+            }
+            return;
+        }
+
+        match action {
+            Action::Quit => self.exit(),
+            Action::NextPage => self.next(),
+            Action::PreviousPage => self.previous(),
+            Action::Page(i) => {
+                self.card_page = 0;
+                self.page = Page::ALL[i];
+            }
+            Action::SlowerSampling => self.change_interval(2.0),
+            Action::FasterSampling => self.change_interval(0.5),
+            Action::Help => {
+                self.help = match self.help {
+                    Some(_) => None,
+                    None => Some(0),
+                }
+            }
+            Action::NextCards => self.card_page = (self.card_page + 1).min(self.card_pages - 1),
+            Action::PreviousCards => self.card_page = self.card_page.saturating_sub(1),
+            Action::Up | Action::Down => {
+                let offset = match action {
+                    Action::Up => -1,
+                    _ => 1,
+                };
+                if let Some(scroll) = &mut self.help {
+                    *scroll = scroll.saturating_add_signed(offset as i16);
+                } else if let Some(view) = &mut self.details {
+                    match offset {
+                        -1 => view.scroll_up(),
+                        _ => view.scroll_down(),
+                    }
+                } else {
+                    self.move_selection(offset);
+                }
+            }
+            Action::Top if self.help.is_some() => self.help = Some(0),
+            Action::Bottom if self.help.is_some() => self.help = Some(u16::MAX),
+            Action::Top => self.move_selection(isize::MIN),
+            Action::Bottom => self.move_selection(isize::MAX),
+            Action::Select if self.filter.editing => self.filter.editing = false,
+            // The PIDs of a recording are from another time or machine, and may belong to
+            // an unrelated process here, so replays can't signal or inspect them.
+            Action::Select | Action::Signal if self.replay.is_some() => {}
+            Action::Select => {
+                if let Some(selected) = self.selected.as_ref().filter(|x| !x.gone) {
+                    self.details = DetailView::new(selected.pid);
+                }
+            }
+            Action::Back if self.filter.editing => self.filter.clear(),
+            Action::Back if self.help.is_some() => self.help = None,
+            Action::Back if self.details.is_some() => self.details = None,
+            Action::Back => match self.selected {
+                Some(_) => self.selected = None,
+                None => self.filter.clear(),
+            },
+            Action::CycleMode => self.monitor.next_mode(),
+            Action::CycleGroup => self.monitor.next_group(),
+            Action::ZoomIn => self.history_view.zoom_in(),
+            Action::ZoomOut => self.history_view.zoom_out(),
+            Action::PanBack => self.history_view.pan(&self.history, false),
+            Action::PanForward => self.history_view.pan(&self.history, true),
+            Action::Cursor => self.history_view.toggle_cursor(&self.history),
+            Action::Live => self.history_view.follow(),
+            Action::Signal => {
+                let target = match &self.details {
+                    Some(view) => (!view.gone).then_some(view.details.pid),
+                    None => self.selected.as_ref().filter(|x| !x.gone).map(|x| x.pid),
+                };
+                if let Some(pid) = target {
+                    self.signal = SignalPicker::new(pid);
+                }
+            }
+            Action::SortPrevious => self.sort.previous(),
+            Action::SortNext => self.sort.next(),
+            Action::Reverse => self.sort.reverse(),
+            Action::Filter => self.filter.editing = true,
+            Action::Tree => self.tree.enabled = !self.tree.enabled,
+            Action::Collapse if self.tree.enabled => {
+                let pid = self.selected.as_ref().map(|x| x.pid);
+                let has_children = self
+                    .process_rows()
+                    .iter()
+                    .any(|x| Some(x.process.pid) == pid && x.children > 0);
+                if let (Some(pid), true) = (pid, has_children) {
+                    self.tree.toggle(pid);
+                }
+            }
+            Action::Collapse => {}
+            Action::DiskColumns => self.sort.toggle_disk_columns(),
+            Action::NextList => {
+                if let Some(view) = &mut self.details {
+                    view.next_list()
+                }
+            }
+            Action::Regex => self.filter.regex = !self.filter.regex,
+            Action::Invert => self.filter.invert = !self.filter.invert,
+            Action::Confirm | Action::Cancel => {}
+            Action::Pause | Action::StepBack | Action::StepForward => {
+                let Some(replay) = &mut self.replay else {
+                    return;
+                };
+                match action {
+                    Action::Pause => replay.toggle_pause(),
+                    Action::StepForward => replay.forward(),
+                    _ if self.history.len() > 1 => {
+                        self.history.pop();
+                        replay.back();
+                    }
+                    _ => {}
+                }
+            }
+            Action::ReplaySlower | Action::ReplayFaster => {
+                if let Some(replay) = &mut self.replay {
+                    match action {
+                        Action::ReplaySlower => replay.change_speed(0.5),
+                        _ => replay.change_speed(2.0),
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        let position = Position::new(mouse_event.column, mouse_event.row);
+        let offset = match mouse_event.kind {
+            MouseEventKind::ScrollUp => -1,
+            MouseEventKind::ScrollDown => 1,
+            MouseEventKind::Down(MouseButton::Left) => 0,
+            _ => return,
+        };
+
+        if let Some(picker) = &mut self.signal {
+            match (&picker.stage, offset) {
+                (Stage::Pick, -1) => picker.previous(),
+                (Stage::Pick, 1) => picker.next(),
+                _ => {}
+            }
+            return;
+        }
+        if self.filter.editing {
+            return;
+        }
+        if let Some(scroll) = &mut self.help {
+            *scroll = scroll.saturating_add_signed((offset * SCROLL) as i16);
+            return;
+        }
+
+        if offset == 0 && self.tabs.contains(position) {
+            if let Some(page) = self.tab_at(position.x) {
+                self.card_page = 0;
+                self.page = page;
+            }
+            return;
+        }
+
+        match (&self.page, offset) {
+            (Page::Stats1 | Page::Stats2 | Page::Monitor, -1) => {
+                self.card_page = self.card_page.saturating_sub(1)
+            }
+            (Page::Stats1 | Page::Stats2 | Page::Monitor, 1) => {
+                self.card_page = (self.card_page + 1).min(self.card_pages - 1)
+            }
+            (Page::History, -1) => self.history_view.zoom_in(),
+            (Page::History, 1) => self.history_view.zoom_out(),
+            (Page::History, _) => {
+                if let Some(plot) = self.charts.iter().find(|x| x.contains(position)) {
+                    self.history_view.inspect(&self.history, *plot, position.x)
+                }
+            }
+            (Page::Processes, _) if self.details.is_some() => {
+                if let Some(view) = &mut self.details {
+                    match offset {
+                        -1 => view.scroll_up(),
+                        1 => view.scroll_down(),
+                        _ => {}
+                    }
+                }
+            }
+            (Page::Processes, 0) if self.process_table.contains(position) => {
+                match pages::processes::row_at(self.process_table, &self.table, position.y) {
+                    Some(row) => {
+                        let rows = self.process_rows();
+                        if let Some(row) = rows.get(row) {
+                            self.selected = Some(Selected::from(row.process));
+                        }
+                    }
+                    None => {
+                        if let Some(column) = self.sort.column_at(self.process_table, position.x) {
+                            self.sort.sort_by(column)
+                        }
+                    }
+                }
+            }
+            (Page::Processes, -1 | 1) => self.move_selection(offset * SCROLL),
             _ => {}
         }
     }
 
+    /// The page whose tab is under `x`. Each tab is its title with a space either side, and
+    /// the tabs are split by a one column divider.
+    fn tab_at(&self, x: u16) -> Option<Page> {
+        let mut left = self.tabs.x;
+        for page in Page::ALL {
+            let right = left + page.title().chars().count() as u16 + 2;
+            if x >= left && x < right {
+                return Some(page);
+            }
+            left = right + 1;
+        }
+        None
+    }
+
+    /// The rows of the process table for the latest sample, in display order.
+    fn process_rows(&self) -> Vec<ProcessRow<'_>> {
+        match self.history.latest() {
+            Some(dp) => {
+                pages::processes::visible(&dp.processes, &self.sort, &self.filter, &self.tree)
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Moves the selection by `offset` rows. Selects the first row when nothing is selected or
+    /// the selected process is no longer shown.
+    fn move_selection(&mut self, offset: isize) {
+        let rows = self.process_rows();
+        let current = self
+            .selected
+            .as_ref()
+            .and_then(|s| rows.iter().position(|x| x.process.pid == s.pid));
+        let index = match current {
+            Some(i) => i
+                .saturating_add_signed(offset)
+                .min(rows.len().saturating_sub(1)),
+            None if offset == isize::MAX => rows.len().saturating_sub(1),
+            None => 0,
+        };
+        let selected = rows.get(index).map(|x| Selected::from(x.process));
+        if selected.is_some() {
+            self.selected = selected;
+        }
+    }
+
+    /// Scales the sampling interval, the sampler thread picks it up after its current sleep.
+    fn change_interval(&mut self, factor: f64) {
+        let current = Duration::from_millis(self.interval.load(Ordering::Relaxed));
+        let interval = current.mul_f64(factor).clamp(MIN_INTERVAL, MAX_INTERVAL);
+        self.interval
+            .store(interval.as_millis() as u64, Ordering::Relaxed);
+    }
+
     fn exit(&mut self) {
         self.exit = true;
     }
 
     fn next(&mut self) {
+        self.card_page = 0;
         self.page = match self.page {
             Page::Stats1 => Page::Stats2,
             Page::Stats2 => Page::Monitor,
-            Page::Monitor => Page::History,
+            Page::Monitor => Page::Sensors,
+            Page::Sensors => Page::Alerts,
+            Page::Alerts => Page::History,
             Page::History => Page::Processes,
             Page::Processes => Page::Stats1,
         }
     }
 
     fn previous(&mut self) {
+        self.card_page = 0;
         self.page = match self.page {
             Page::Stats1 => Page::Processes,
             Page::Stats2 => Page::Stats1,
             Page::Monitor => Page::Stats2,
-            Page::History => Page::Monitor,
+            Page::Sensors => Page::Monitor,
+            Page::Alerts => Page::Sensors,
+            Page::History => Page::Alerts,
             Page::Processes => Page::History,
         }
     }
 }
 
+pub fn get_bytes(bytes: f64) -> String {
+    let units = ["B", "Kb", "Mb", "Gb", "Tb"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.2} {}", value, units[unit])
+}
+
 pub fn get_time(seconds: u64) -> String {
     let div = |x: f64, d: f64| -> f64 { (x / d) as u64 as f64 };
     let hrs = div(seconds as f64, 3600.0);
@@ -0,0 +1,70 @@
+use chrono::{Local, TimeZone};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Color,
+    text::Text,
+    widgets::{Cell, Row, Table},
+    Frame,
+};
+
+use crate::{
+    alerts::{Alerts, Event, Level},
+    pages::get_block,
+};
+
+pub fn draw(frame: &mut Frame, area: Rect, alerts: &Alerts) {
+    // The active alerts get their rows plus the borders and header.
+    let [active, log] = Layout::vertical([
+        Constraint::Max(alerts.active.len().max(1) as u16 + 3),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+
+    frame.render_widget(
+        table(
+            format!("Active ({})", alerts.active.len()),
+            alerts.active.values(),
+        ),
+        active,
+    );
+    frame.render_widget(
+        table(
+            format!("Log ({})", alerts.log.len()),
+            alerts.log.iter().rev(),
+        ),
+        log,
+    );
+}
+
+fn time(timestamp: u64) -> String {
+    Local
+        .timestamp_millis_opt(timestamp as i64)
+        .single()
+        .map(|x| x.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+fn table<'a>(title: String, events: impl Iterator<Item = &'a Event>) -> Table<'a> {
+    let rows = events.map(|x| {
+        let state = match x.level {
+            Level::Normal => format!("cleared ({})", x.previous.name()),
+            level => level.name().to_string(),
+        };
+        Row::new(vec![
+            Cell::from(time(x.timestamp)),
+            Cell::from(x.metric.clone()),
+            Cell::from(Text::from(state).style(x.level.style(Color::Reset))),
+            Cell::from(format!("{:.1}{}", x.value, x.unit)),
+        ])
+    });
+
+    let widths = [
+        Constraint::Length(19),
+        Constraint::Fill(3),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+    ];
+    Table::new(rows, widths)
+        .header(Row::new(vec!["TIME", "METRIC", "STATE", "VALUE"]))
+        .block(get_block().title(title))
+}
@@ -0,0 +1,279 @@
+use chrono::{Local, TimeZone};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    text::Line,
+    widgets::{Paragraph, Row, Table, Wrap},
+    Frame,
+};
+use std::time::Instant;
+use sysinfo::Pid;
+
+use crate::{
+    data::{details::ProcessDetails, store::MetricStore},
+    get_time,
+    pages::{
+        get_block,
+        history::{self, HistoryView},
+    },
+    theme,
+};
+
+/// The lists at the bottom of the detail view, each scrolled on its own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum List {
+    #[default]
+    Environment,
+    OpenFiles,
+}
+
+/// The detail view of the selected process. `gone` is set once the process exits, or its
+/// PID is given to another process, the last details gathered are kept on screen.
+#[derive(Clone, Debug)]
+pub struct DetailView {
+    pub details: ProcessDetails,
+    /// When `details` were gathered, for the disk rates.
+    refreshed: Instant,
+    /// The list the arrow keys scroll.
+    pub focus: List,
+    pub environment_scroll: u16,
+    pub files_scroll: u16,
+    pub gone: bool,
+}
+
+impl DetailView {
+    pub fn new(pid: Pid) -> Option<Self> {
+        Some(Self {
+            details: ProcessDetails::new(pid)?,
+            refreshed: Instant::now(),
+            focus: List::default(),
+            environment_scroll: 0,
+            files_scroll: 0,
+            gone: false,
+        })
+    }
+
+    pub fn refresh(&mut self) {
+        if self.gone {
+            return;
+        }
+        match ProcessDetails::new(self.details.pid) {
+            Some(mut details) if details.start_time == self.details.start_time => {
+                details.rates_since(&self.details, self.refreshed.elapsed());
+                self.details = details;
+                self.refreshed = Instant::now();
+            }
+            _ => self.gone = true,
+        }
+    }
+
+    fn scroll(&mut self) -> &mut u16 {
+        match self.focus {
+            List::Environment => &mut self.environment_scroll,
+            List::OpenFiles => &mut self.files_scroll,
+        }
+    }
+
+    pub fn scroll_up(&mut self) {
+        let scroll = self.scroll();
+        *scroll = scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        let scroll = self.scroll();
+        *scroll = scroll.saturating_add(1);
+    }
+
+    pub fn next_list(&mut self) {
+        self.focus = match self.focus {
+            List::Environment => List::OpenFiles,
+            List::OpenFiles => List::Environment,
+        };
+    }
+}
+
+pub fn draw(
+    frame: &mut Frame,
+    area: Rect,
+    view: &DetailView,
+    history: &MetricStore,
+    history_view: &HistoryView,
+) {
+    let details = &view.details;
+    let [top, command, charts, bottom] = Layout::vertical([
+        Constraint::Length(14),
+        Constraint::Length(5),
+        Constraint::Length(10),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+    let [info_area, limits_area] =
+        Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).areas(top);
+    let [env_area, files_area] =
+        Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).areas(bottom);
+
+    frame.render_widget(info(view), info_area);
+    frame.render_widget(limits(details), limits_area);
+    frame.render_widget(
+        Paragraph::new(details.args.join(" "))
+            .wrap(Wrap { trim: false })
+            .block(get_block().title("Command")),
+        command,
+    );
+    history::draw_process(frame, charts, history, history_view, details.pid);
+    frame.render_widget(
+        list(
+            format!("Environment ({})", details.environment.len()),
+            &details.environment,
+            view.environment_scroll,
+            view.focus == List::Environment,
+        ),
+        env_area,
+    );
+    frame.render_widget(
+        list(
+            format!("Open Files ({})", details.open_files.len()),
+            &details.open_files,
+            view.files_scroll,
+            view.focus == List::OpenFiles,
+        ),
+        files_area,
+    );
+}
+
+fn info(view: &DetailView) -> Table<'static> {
+    let details = &view.details;
+    let mb = |x: f64| format!("{:.2} Mb", x / 1024_f64 / 1024_f64);
+    let path = |x: &Option<std::path::PathBuf>| match x {
+        Some(x) => x.to_string_lossy().to_string(),
+        None => "-".to_string(),
+    };
+
+    let rows = vec![
+        Row::new(vec!["PID".to_string(), details.pid.to_string()]),
+        Row::new(vec!["Name".to_string(), details.name.clone()]),
+        Row::new(vec![
+            "Parent".to_string(),
+            details
+                .parent
+                .map(|x| x.to_string())
+                .unwrap_or("-".to_string()),
+        ]),
+        Row::new(vec![
+            "Status".to_string(),
+            match view.gone {
+                true => "Exited".to_string(),
+                false => details.status.clone(),
+            },
+        ]),
+        Row::new(vec![
+            "Threads".to_string(),
+            details
+                .threads
+                .map(|x| x.to_string())
+                .unwrap_or("-".to_string()),
+        ]),
+        Row::new(vec![
+            "Started".to_string(),
+            format!(
+                "{} ({} ago)",
+                Local
+                    .timestamp_opt(details.start_time as i64, 0)
+                    .single()
+                    .map(|x| x.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or("-".to_string()),
+                get_time(details.run_time)
+            ),
+        ]),
+        Row::new(vec!["Exe".to_string(), path(&details.exe)]),
+        Row::new(vec!["Cwd".to_string(), path(&details.cwd)]),
+        Row::new(vec![
+            "Disk Read".to_string(),
+            format!(
+                "{}/s ({} total)",
+                mb(details.read_rate),
+                mb(details.total_read_bytes as f64)
+            ),
+        ]),
+        Row::new(vec![
+            "Disk Write".to_string(),
+            format!(
+                "{}/s ({} total)",
+                mb(details.write_rate),
+                mb(details.total_written_bytes as f64)
+            ),
+        ]),
+    ];
+
+    let widths = [Constraint::Percentage(20), Constraint::Fill(1)];
+    let mut block = get_block().title(format!("Process {}", details.pid));
+    if view.gone {
+        block = block.title_bottom(
+            Line::from(" ✗ Process gone ")
+                .style(theme::get().critical_style())
+                .centered(),
+        );
+    }
+    Table::new(rows, widths).block(block)
+}
+
+fn limits(details: &ProcessDetails) -> Table<'static> {
+    let rows = details
+        .limits
+        .iter()
+        .map(|x| {
+            Row::new(vec![
+                x.name.clone(),
+                x.soft.clone(),
+                x.hard.clone(),
+                x.units.clone(),
+            ])
+        })
+        .collect::<Vec<Row>>();
+
+    let widths = [
+        Constraint::Fill(3),
+        Constraint::Fill(2),
+        Constraint::Fill(2),
+        Constraint::Fill(1),
+    ];
+    Table::new(rows, widths)
+        .header(Row::new(vec!["Limit", "Soft", "Hard", "Units"]))
+        .block(get_block().title("Limits"))
+}
+
+/// A scrollable list, the one the arrow keys scroll is marked in its title.
+fn list(title: String, lines: &[String], scroll: u16, focused: bool) -> Paragraph<'_> {
+    let title = match focused {
+        true => format!("▶ {}", title),
+        false => title,
+    };
+    Paragraph::new(
+        lines
+            .iter()
+            .map(|x| Line::from(x.as_str()))
+            .collect::<Vec<Line>>(),
+    )
+    .scroll((scroll, 0))
+    .block(get_block().title(title))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    #[test]
+    fn a_new_process_with_the_pid_is_gone() {
+        let mut view = DetailView::new(Pid::from_u32(process::id())).unwrap();
+        view.refresh();
+        assert!(!view.gone);
+
+        // As if the process exited and its PID went to one started later.
+        view.details.start_time -= 1;
+        view.refresh();
+        assert!(view.gone);
+        let start_time = view.details.start_time;
+        view.refresh();
+        assert_eq!(view.details.start_time, start_time);
+    }
+}
@@ -0,0 +1,46 @@
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    keymap::{Keymap, Scope},
+    pages::{centered, get_block},
+    theme,
+};
+
+/// Draws every key binding over the page, grouped by where they apply. Returns `scroll`
+/// limited to what can be scrolled.
+pub fn draw(frame: &mut Frame, area: Rect, keymap: &Keymap, scroll: u16) -> u16 {
+    let mut lines = Vec::new();
+    for scope in Scope::ALL {
+        let bindings = keymap.list(scope);
+        if bindings.is_empty() {
+            continue;
+        }
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(
+            Span::raw(scope.title()).style(Style::new().fg(theme::get().title).bold()),
+        ));
+        for (keys, description) in bindings {
+            lines.push(Line::from(format!("  {:<16}{}", keys, description)));
+        }
+    }
+
+    let area = centered(area, 72, area.height.saturating_sub(2));
+    let visible = area.height.saturating_sub(2);
+    let scroll = scroll.min((lines.len() as u16).saturating_sub(visible));
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .scroll((scroll, 0))
+            .block(get_block().title("Help")),
+        area,
+    );
+    scroll
+}
@@ -1,104 +1,577 @@
+use chrono::{Local, TimeZone};
 use ratatui::{
-    layout::{Constraint, Layout, Rect},
+    layout::{Constraint, Layout, Margin, Rect},
     style::{Style, Stylize},
     symbols,
-    widgets::{Axis, Chart, Dataset, GraphType},
+    text::{Line, Span},
+    widgets::{Axis, Chart, Dataset, GraphType, Paragraph, Wrap},
     Frame,
 };
 
-use crate::{data::Data, pages::get_block};
+use sysinfo::Pid;
 
-const Y: usize = 3;
-const X: usize = 1;
+use crate::{
+    config,
+    data::{
+        sensors::SensorKind,
+        store::{Bucket, MetricStore, Stat, BUCKET},
+        Data,
+    },
+    get_bytes,
+    keymap::{Action, Keymap, Scope},
+    pages::{get_block, grid},
+    theme,
+};
+
+/// The spans of time the charts can be zoomed to, in seconds.
+const ZOOMS: [(u64, &str); 5] = [
+    (60, "1m"),
+    (300, "5m"),
+    (3600, "1h"),
+    (21600, "6h"),
+    (86400, "1d"),
+];
+
+/// The part of the history the charts show. The window either follows the newest sample or,
+/// once panned, stays on a fixed stretch of time.
+#[derive(Clone, Debug, Default)]
+pub struct HistoryView {
+    zoom: usize,
+    /// The timestamp the window ends at, `None` follows the newest sample.
+    end: Option<u64>,
+    /// The timestamp of the sample under the cursor.
+    cursor: Option<u64>,
+}
+
+impl HistoryView {
+    fn span(&self) -> u64 {
+        ZOOMS[self.zoom].0 * 1000
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom = self.zoom.saturating_sub(1);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.zoom = (self.zoom + 1).min(ZOOMS.len() - 1);
+    }
+
+    /// Goes back to following the newest sample.
+    pub fn follow(&mut self) {
+        self.end = None;
+    }
+
+    pub fn toggle_cursor(&mut self, store: &MetricStore) {
+        self.cursor = match self.cursor {
+            Some(_) => None,
+            None => {
+                let end = self.window_end(store);
+                store
+                    .samples()
+                    .iter()
+                    .rev()
+                    .find(|x| x.timestamp <= end)
+                    .map(|x| x.timestamp)
+            }
+        };
+    }
+
+    /// Moves the cursor one sample, or pans the window by a quarter of its span without one.
+    /// The cursor only visits the samples kept whole, panning reaches back to the oldest bucket.
+    pub fn pan(&mut self, store: &MetricStore, forward: bool) {
+        let (Some(oldest), Some(last)) = (store.oldest(), store.latest()) else {
+            return;
+        };
+        let history = store.samples();
+
+        let end = match self.cursor {
+            Some(cursor) => {
+                let i = history
+                    .iter()
+                    .position(|x| x.timestamp >= cursor)
+                    .unwrap_or(history.len() - 1);
+                let i = match forward {
+                    true => (i + 1).min(history.len() - 1),
+                    false => i.saturating_sub(1),
+                };
+                let cursor = history[i].timestamp;
+                self.cursor = Some(cursor);
+                // Only move the window when the cursor leaves it.
+                let end = self.window_end(store);
+                match (cursor > end, cursor + self.span() < end) {
+                    (true, _) => cursor,
+                    (_, true) => cursor + self.span(),
+                    _ => end,
+                }
+            }
+            None => {
+                let step = self.span() / 4;
+                match forward {
+                    true => self.window_end(store).saturating_add(step),
+                    false => self.window_end(store).saturating_sub(step),
+                }
+            }
+        };
+
+        let oldest_end = (oldest + self.span()).min(last.timestamp);
+        self.end = match end >= last.timestamp {
+            true => None,
+            false => Some(end.max(oldest_end)),
+        };
+    }
+
+    fn window_end(&self, store: &MetricStore) -> u64 {
+        let last = store.latest().map_or(0, |x| x.timestamp);
+        self.end.unwrap_or(last).min(last)
+    }
+
+    fn window(&self, store: &MetricStore) -> Window {
+        let latest = store.latest().map_or(0, |x| x.timestamp);
+        let end = self.window_end(store);
+        Window {
+            latest,
+            start: seconds(end.saturating_sub(self.span()), latest),
+            end: seconds(end, latest),
+            cursor: self.cursor.map(|x| seconds(x, latest)),
+        }
+    }
+
+    /// Puts the cursor on the sample nearest to `column` of a chart plotted in `plot`.
+    pub fn inspect(&mut self, store: &MetricStore, plot: Rect, column: u16) {
+        let window = self.window(store);
+        let fraction =
+            column.saturating_sub(plot.left()) as f64 / plot.width.saturating_sub(1).max(1) as f64;
+        let x = window.start + fraction.min(1.0) * (window.end - window.start);
+        let nearest = store
+            .samples()
+            .iter()
+            .map(|data| seconds(data.timestamp, window.latest))
+            .filter(|at| *at >= window.start && *at <= window.end)
+            .min_by(|a, b| (a - x).abs().total_cmp(&(b - x).abs()));
+        if let Some(at) = nearest {
+            self.cursor = Some((window.latest as f64 + at * 1000.0).round() as u64);
+        }
+    }
+
+    pub fn status(&self, keymap: &Keymap) -> String {
+        let mut status = format!(
+            " | {} {} | {}",
+            keymap.hints(
+                Scope::History,
+                &[("Zoom", &[Action::ZoomIn, Action::ZoomOut])]
+            ),
+            ZOOMS[self.zoom].1,
+            keymap.hints(
+                Scope::History,
+                &[
+                    ("Pan", &[Action::PanBack, Action::PanForward]),
+                    ("Cursor", &[Action::Cursor]),
+                ]
+            )
+        );
+        if self.end.is_some() {
+            status.push_str(" | ");
+            status.push_str(&keymap.hints(Scope::History, &[("Live", &[Action::Live])]));
+        }
+        status
+    }
+}
+
+/// The width of a time label on the X axis.
+const TIME_WIDTH: u16 = 8;
+
+/// Where a chart drawn in `area` plots its lines. This follows ratatui's chart layout: the
+/// Y labels (or the half of the first time label left of the axis) and the Y axis take the
+/// left, the time labels and X axis the bottom two rows.
+fn plot_area(area: Rect, label_width: u16) -> Rect {
+    let inner = area.inner(Margin::new(1, 1));
+    let left = (label_width.max(TIME_WIDTH - 1).min(inner.width / 3) + 1).min(inner.width);
+    Rect::new(
+        inner.x + left,
+        inner.y,
+        inner.width - left,
+        inner.height.saturating_sub(2),
+    )
+}
+
+/// Seconds from the newest sample at `latest` to `timestamp`, so the newest sample is at 0.
+fn seconds(timestamp: u64, latest: u64) -> f64 {
+    (timestamp as f64 - latest as f64) / 1000.0
+}
+
+/// The X bounds of the charts, in seconds relative to the newest sample.
+struct Window {
+    latest: u64,
+    start: f64,
+    end: f64,
+    /// Where the cursor is, if it is shown.
+    cursor: Option<f64>,
+}
+
+/// A vertical line at the cursor.
+type Cursor = Option<[(f64, f64); 2]>;
+
+impl Window {
+    fn time(&self, x: f64) -> String {
+        let timestamp = self.latest as i64 + (x * 1000.0) as i64;
+        match Local.timestamp_millis_opt(timestamp).single() {
+            Some(time) => time.format("%H:%M:%S").to_string(),
+            None => String::new(),
+        }
+    }
+
+    /// The cursor line of a chart whose Y axis goes up to `max`. Lines with an end outside
+    /// the chart aren't drawn at all, so it can't be taller than the chart.
+    fn cursor(&self, max: f64) -> Cursor {
+        self.cursor.map(|x| [(x, 0.0), (x, max)])
+    }
+
+    fn axis<'a>(&self) -> Axis<'a> {
+        let middle = (self.start + self.end) / 2.0;
+        Axis::default().bounds([self.start, self.end]).labels([
+            self.time(self.start),
+            self.time(middle),
+            self.time(self.end),
+        ])
+    }
+
+    /// The samples in the window, with one either side so lines run to the edges.
+    fn samples<'a>(&self, store: &'a MetricStore) -> Vec<(f64, &'a Data)> {
+        let points = store
+            .samples()
+            .iter()
+            .map(|x| (seconds(x.timestamp, self.latest), x))
+            .collect::<Vec<(f64, &Data)>>();
+        let first = points
+            .iter()
+            .position(|x| x.0 >= self.start)
+            .unwrap_or(points.len());
+        let last = points
+            .iter()
+            .rposition(|x| x.0 <= self.end)
+            .map_or(0, |x| x + 1);
+        points[first.saturating_sub(1)..(last + 1).min(points.len()).max(first)].to_vec()
+    }
+
+    /// The buckets overlapping the window, placed at their middle. The newest bucket can
+    /// reach past the oldest whole sample, it is kept behind it so the lines join up.
+    fn buckets<'a>(&self, store: &'a MetricStore) -> Vec<(f64, &'a Bucket)> {
+        let width = BUCKET as f64 / 1000.0;
+        let first = store
+            .samples()
+            .front()
+            .map_or(f64::MAX, |x| seconds(x.timestamp, self.latest));
+        store
+            .buckets()
+            .iter()
+            .map(|x| (seconds(x.start, self.latest), x))
+            .filter(|(x, _)| *x + width >= self.start - width && *x <= self.end)
+            .map(|(x, bucket)| ((x + width / 2.0).min(first), bucket))
+            .collect()
+    }
+}
+
+/// A device name with a pair of rates, e.g. received/transmitted.
+type DeviceRates = (String, HistoryData, HistoryData);
 
 #[derive(Debug)]
 struct HistoryData(pub Vec<(f64, f64)>);
 
 impl HistoryData {
-    fn from_cores(history: &[Data]) -> Vec<Self> {
+    fn from_cores(samples: &[(f64, &Data)]) -> Vec<Self> {
         let mut lst: Vec<Vec<(f64, f64)>> = Vec::new();
 
-        for (x, data) in history.iter().enumerate() {
+        for (x, data) in samples.iter() {
             for (y, core) in data.cpu.cores.iter().enumerate() {
                 if let Some(d) = lst.get_mut(y) {
-                    d.push((x as f64, core.usage as f64));
+                    d.push((*x, core.usage as f64));
                 } else {
-                    lst.push(vec![(x as f64, core.usage as f64)]);
+                    lst.push(vec![(*x, core.usage as f64)]);
                 }
             }
         }
 
-        lst.into_iter().map(|x| HistoryData(x)).collect()
+        lst.into_iter().map(HistoryData).collect()
     }
 
-    fn from_cpu(history: &[Data]) -> Self {
+    /// The mean of one value of every bucket, and its minimum and maximum.
+    fn from_buckets(buckets: &[(f64, &Bucket)], stat: fn(&Bucket) -> Stat) -> (Self, [Self; 2]) {
+        let stats = buckets
+            .iter()
+            .map(|(x, bucket)| (*x, stat(bucket)))
+            .collect::<Vec<(f64, Stat)>>();
+        (
+            Self(stats.iter().map(|(x, s)| (*x, s.avg())).collect()),
+            [
+                Self(stats.iter().map(|(x, s)| (*x, s.min)).collect()),
+                Self(stats.iter().map(|(x, s)| (*x, s.max)).collect()),
+            ],
+        )
+    }
+
+    fn from_cpu(samples: &[(f64, &Data)]) -> Self {
         Self(
-            history
+            samples
                 .iter()
-                .enumerate()
-                .map(|x| (x.0 as f64, x.1.cpu.usage as f64))
+                .map(|(x, data)| (*x, data.cpu.usage as f64))
                 .collect(),
         )
     }
 
-    fn from_mem(history: &[Data]) -> (Self, Self) {
+    /// `self` with the downsampled points before it.
+    fn after(mut self, mut older: Self) -> Self {
+        older.0.append(&mut self.0);
+        older
+    }
+
+    /// The CPU usage and resident memory (Mb) of one process, for the samples it was alive in.
+    fn from_process(samples: &[(f64, &Data)], pid: Pid) -> (Self, Self) {
+        let points = samples
+            .iter()
+            .filter_map(|(x, data)| {
+                let p = data.processes.iter().find(|p| p.pid == pid)?;
+                Some((*x, p.cpu as f64, p.memory as f64 / 1024_f64 / 1024_f64))
+            })
+            .collect::<Vec<(f64, f64, f64)>>();
+        (
+            Self(points.iter().map(|x| (x.0, x.1)).collect()),
+            Self(points.iter().map(|x| (x.0, x.2)).collect()),
+        )
+    }
+
+    /// The receive and transmit rates of every interface seen in the history.
+    fn from_networks(samples: &[(f64, &Data)]) -> Vec<DeviceRates> {
+        let mut lst: Vec<DeviceRates> = Vec::new();
+
+        for (x, data) in samples.iter() {
+            for network in data.networks.iter() {
+                let i = match lst.iter().position(|n| n.0 == network.name) {
+                    Some(i) => i,
+                    None => {
+                        lst.push((network.name.clone(), Self(Vec::new()), Self(Vec::new())));
+                        lst.len() - 1
+                    }
+                };
+                lst[i].1 .0.push((*x, network.rx_rate));
+                lst[i].2 .0.push((*x, network.tx_rate));
+            }
+        }
+
+        lst
+    }
+
+    /// The read and write rates and the utilisation of every block device seen in the history.
+    fn from_disk_io(samples: &[(f64, &Data)]) -> (Vec<DeviceRates>, Vec<(String, Self)>) {
+        let mut rates: Vec<DeviceRates> = Vec::new();
+        let mut busy: Vec<(String, Self)> = Vec::new();
+
+        for (x, data) in samples.iter() {
+            for io in data.disk_io.iter() {
+                let i = match rates.iter().position(|d| d.0 == io.name) {
+                    Some(i) => i,
+                    None => {
+                        rates.push((io.name.clone(), Self(Vec::new()), Self(Vec::new())));
+                        busy.push((io.name.clone(), Self(Vec::new())));
+                        rates.len() - 1
+                    }
+                };
+                rates[i].1 .0.push((*x, io.read_rate));
+                rates[i].2 .0.push((*x, io.write_rate));
+                busy[i].1 .0.push((*x, io.utilisation));
+            }
+        }
+
+        (rates, busy)
+    }
+
+    /// The reading of every temperature sensor seen in the history.
+    fn from_temperatures(samples: &[(f64, &Data)]) -> Vec<(String, Self)> {
+        let mut lst: Vec<(String, Self)> = Vec::new();
+
+        for (x, data) in samples.iter() {
+            for sensor in data.sensors.iter() {
+                if sensor.kind != SensorKind::Temperature {
+                    continue;
+                }
+                let name = sensor.name();
+                let i = match lst.iter().position(|s| s.0 == name) {
+                    Some(i) => i,
+                    None => {
+                        lst.push((name, Self(Vec::new())));
+                        lst.len() - 1
+                    }
+                };
+                lst[i].1 .0.push((*x, sensor.value));
+            }
+        }
+
+        lst
+    }
+
+    fn from_mem(samples: &[(f64, &Data)]) -> (Self, Self) {
         (
             Self(
-                history
+                samples
                     .iter()
-                    .enumerate()
-                    .map(|x| {
-                        (
-                            x.0 as f64,
-                            x.1.memory.used_mem as f64 / x.1.memory.total_mem as f64 * 100.0,
-                        )
-                    })
+                    .map(|(x, data)| (*x, data.memory.mem_percent()))
                     .collect(),
             ),
             Self(
-                history
+                samples
                     .iter()
-                    .enumerate()
-                    .map(|x| {
-                        (
-                            x.0 as f64,
-                            x.1.memory.used_swap as f64 / x.1.memory.total_swap as f64 * 100.0,
-                        )
-                    })
+                    .map(|(x, data)| (*x, data.memory.swap_percent()))
                     .collect(),
             ),
         )
     }
 }
 
-pub fn draw(frame: &mut Frame, area: Rect, history: &[Data]) {
-    let vertical = Layout::vertical([Constraint::Ratio(1, Y as u32); Y]);
-    let horizontal = Layout::horizontal([Constraint::Ratio(1, X as u32); X]);
-    let v = vertical.areas::<Y>(area);
-    let mut areas = Vec::new();
+/// How many charts the history page draws.
+pub const CHARTS: usize = 7;
 
-    for a in v {
-        for l in horizontal.areas::<X>(a) {
-            areas.push(l);
+/// Returns where each chart plots its lines, so a click can be turned into a time.
+pub fn draw(frame: &mut Frame, area: Rect, store: &MetricStore, view: &HistoryView) -> Vec<Rect> {
+    let window = view.window(store);
+    let cursor = view
+        .cursor
+        .and_then(|x| store.samples().iter().find(|data| data.timestamp == x));
+    let area = match cursor {
+        Some(data) => {
+            let [charts, values] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(4)]).areas(area);
+            frame.render_widget(readout(data), values);
+            charts
         }
+        None => area,
+    };
+    let areas = grid(area, config::get().layout.history);
+
+    let samples = window.samples(store);
+    let buckets = window.buckets(store);
+    let (cpu_avg, cpu_band) = HistoryData::from_buckets(&buckets, |x| x.cpu);
+    let (mem_avg, mem_band) = HistoryData::from_buckets(&buckets, |x| x.memory);
+    let (swap_avg, _) = HistoryData::from_buckets(&buckets, |x| x.swap);
+    let (mem, swap) = HistoryData::from_mem(&samples);
+
+    let cores_data = HistoryData::from_cores(&samples);
+    let cpu_data = HistoryData::from_cpu(&samples).after(cpu_avg);
+    let mem_data = (mem.after(mem_avg), swap.after(swap_avg));
+    let net_data = HistoryData::from_networks(&samples);
+    let (disk_data, busy_data) = HistoryData::from_disk_io(&samples);
+    let temp_data = HistoryData::from_temperatures(&samples);
+
+    let percent = window.cursor(100.0);
+    let net_cursor = window.cursor(throughput_max(&net_data));
+    let disk_cursor = window.cursor(throughput_max(&disk_data));
+    let temp_cursor = window.cursor(temperature_max(&temp_data));
+
+    // Only the throughput labels can be wider than the start of the first time label.
+    let widgets: [_; CHARTS] = [
+        (cpu(&cpu_data, &cpu_band, &window, &percent), 0),
+        (cores(&cores_data, &window, &percent), 0),
+        (memory(&mem_data, &mem_band, &window, &percent), 0),
+        (
+            throughput("Network", ["↓", "↑"], &net_data, &window, &net_cursor),
+            label_width(&throughput_labels(&net_data)),
+        ),
+        (
+            throughput("Disk I/O", ["R", "W"], &disk_data, &window, &disk_cursor),
+            label_width(&throughput_labels(&disk_data)),
+        ),
+        (disk_busy(&busy_data, &window, &percent), 0),
+        (temperatures(&temp_data, &window, &temp_cursor), 0),
+    ];
+
+    let mut plots = Vec::new();
+    for ((widget, label_width), area) in widgets.into_iter().zip(areas) {
+        frame.render_widget(widget, area);
+        plots.push(plot_area(area, label_width));
     }
+    plots
+}
 
-    let cores_data = HistoryData::from_cores(history);
-    let cpu_data = HistoryData::from_cpu(history);
-    let mem_data = HistoryData::from_mem(history);
+/// Charts the CPU usage and resident memory of a single process over the samples kept whole.
+pub fn draw_process(
+    frame: &mut Frame,
+    area: Rect,
+    store: &MetricStore,
+    view: &HistoryView,
+    pid: Pid,
+) {
+    let [cpu_area, mem_area] =
+        Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).areas(area);
+    let window = view.window(store);
+    let (cpu_data, mem_data) = HistoryData::from_process(&window.samples(store), pid);
 
-    let widgets = vec![cpu(&cpu_data), cores(&cores_data), memory(&mem_data)];
+    let percent = window.cursor(100.0);
+    let mem_cursor = window.cursor(process_memory_max(&mem_data));
 
-    for (i, widget) in widgets.into_iter().enumerate() {
-        if i < areas.len() {
-            frame.render_widget(widget, areas[i]);
-        }
+    frame.render_widget(cpu(&cpu_data, &[], &window, &percent), cpu_area);
+    frame.render_widget(process_memory(&mem_data, &window, &mem_cursor), mem_area);
+}
+
+/// The exact values of the sample under the cursor.
+fn readout(data: &Data) -> Paragraph<'_> {
+    let label = |x: String| Span::styled(x, Style::new().fg(theme::get().title).bold());
+    let time = Local
+        .timestamp_millis_opt(data.timestamp as i64)
+        .single()
+        .map(|x| x.format("%H:%M:%S").to_string())
+        .unwrap_or_default();
+
+    let mut spans = vec![
+        label("CPU ".to_string()),
+        Span::raw(format!("{:.1}%  ", data.cpu.usage)),
+        label("Memory ".to_string()),
+        Span::raw(format!("{:.1}%  ", data.memory.mem_percent())),
+    ];
+    for network in data.networks.iter() {
+        spans.push(label(format!("{} ", network.name)));
+        spans.push(Span::raw(format!(
+            "↓ {}/s ↑ {}/s  ",
+            get_bytes(network.rx_rate),
+            get_bytes(network.tx_rate)
+        )));
+    }
+    for io in data.disk_io.iter() {
+        spans.push(label(format!("{} ", io.name)));
+        spans.push(Span::raw(format!(
+            "R {}/s W {}/s {:.0}%  ",
+            get_bytes(io.read_rate),
+            get_bytes(io.write_rate),
+            io.utilisation
+        )));
+    }
+    for sensor in data
+        .sensors
+        .iter()
+        .filter(|x| x.kind == SensorKind::Temperature)
+    {
+        spans.push(label(format!("{} ", sensor.name())));
+        spans.push(Span::raw(format!("{:.1}°C  ", sensor.value)));
     }
+
+    Paragraph::new(Line::from(spans))
+        .wrap(Wrap { trim: true })
+        .block(get_block().title(format!("Cursor {}", time)))
 }
 
-fn default_chart<'a>(data: Vec<Dataset<'a>>) -> Chart<'a> {
-    Chart::new(data)
-        .x_axis(Axis::default().title("Time").bounds([0.0, 100.0]))
+/// `datasets` with the cursor line added.
+fn with_cursor<'a>(mut datasets: Vec<Dataset<'a>>, cursor: &'a Cursor) -> Vec<Dataset<'a>> {
+    if let Some(line) = cursor {
+        datasets.push(
+            Dataset::default()
+                .graph_type(GraphType::Line)
+                .style(Style::new().fg(theme::get().cursor))
+                .data(line),
+        );
+    }
+    datasets
+}
+
+fn default_chart<'a>(data: Vec<Dataset<'a>>, window: &'a Window, cursor: &'a Cursor) -> Chart<'a> {
+    Chart::new(with_cursor(data, cursor))
+        .x_axis(window.axis())
         .y_axis(
             Axis::default()
                 .bounds([0.0, 100.0])
@@ -106,58 +579,244 @@ fn default_chart<'a>(data: Vec<Dataset<'a>>) -> Chart<'a> {
         )
 }
 
-fn cpu<'a>(data: &'a HistoryData) -> Chart<'a> {
+/// The minimum and maximum of the downsampled points, drawn dimmed behind the mean.
+fn band(band: &[HistoryData]) -> Vec<Dataset<'_>> {
+    band.iter()
+        .map(|x| {
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().fg(theme::get().band))
+                .data(&x.0)
+        })
+        .collect()
+}
+
+fn cpu<'a>(
+    data: &'a HistoryData,
+    range: &'a [HistoryData],
+    window: &'a Window,
+    cursor: &'a Cursor,
+) -> Chart<'a> {
     let block = get_block().title("CPU");
-    default_chart(vec![Dataset::default()
-        .name("CPU")
-        .marker(symbols::Marker::Braille)
-        .graph_type(GraphType::Line)
-        .style(Style::new().blue())
-        .data(&data.0)])
+    let mut datasets = band(range);
+    datasets.push(
+        Dataset::default()
+            .name("CPU")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().fg(theme::get().primary))
+            .data(&data.0),
+    );
+    default_chart(datasets, window, cursor)
+        .legend_position(None)
+        .block(block)
+}
+
+fn process_memory_max(data: &HistoryData) -> f64 {
+    data.0.iter().map(|x| x.1).fold(1.0, f64::max) * 1.1
+}
+
+fn process_memory<'a>(data: &'a HistoryData, window: &'a Window, cursor: &'a Cursor) -> Chart<'a> {
+    let block = get_block().title("Memory (Mb)");
+    let max = process_memory_max(data);
+    Chart::new(with_cursor(
+        vec![Dataset::default()
+            .name("Memory")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().fg(theme::get().secondary))
+            .data(&data.0)],
+        cursor,
+    ))
+    .x_axis(window.axis())
+    .y_axis(Axis::default().bounds([0.0, max]).labels([
+        "0".to_string(),
+        format!("{:.0}", max / 2.0),
+        format!("{:.0}", max),
+    ]))
     .legend_position(None)
     .block(block)
 }
 
-fn memory<'a>(data: &'a (HistoryData, HistoryData)) -> Chart<'a> {
+fn memory<'a>(
+    data: &'a (HistoryData, HistoryData),
+    range: &'a [HistoryData],
+    window: &'a Window,
+    cursor: &'a Cursor,
+) -> Chart<'a> {
     let block = get_block().title("Memory");
-    default_chart(vec![
+    let mut datasets = band(range);
+    datasets.extend([
         Dataset::default()
             .name("Memory")
             .marker(symbols::Marker::Braille)
             .graph_type(GraphType::Line)
-            .style(Style::new().blue())
+            .style(Style::new().fg(theme::get().primary))
             .data(&data.0 .0),
         Dataset::default()
             .name("Swap")
             .marker(symbols::Marker::Braille)
             .graph_type(GraphType::Line)
-            .style(Style::new().yellow())
+            .style(Style::new().fg(theme::get().secondary))
             .data(&data.1 .0),
-    ])
-    .legend_position(Some(ratatui::widgets::LegendPosition::BottomLeft))
-    .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
-    .block(block)
+    ]);
+    default_chart(datasets, window, cursor)
+        .legend_position(Some(ratatui::widgets::LegendPosition::BottomLeft))
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
+        .block(block)
 }
 
-fn cores<'a>(data: &'a [HistoryData]) -> Chart<'a> {
+fn cores<'a>(data: &'a [HistoryData], window: &'a Window, cursor: &'a Cursor) -> Chart<'a> {
     let block = get_block().title("Cores");
+    let samples = data.first().map_or(0, |x| x.0.len()).max(1) as u32;
     default_chart(
-        data.into_iter()
+        data.iter()
             .enumerate()
             .map(|(i, x)| {
                 Dataset::default()
                     .name(format!("cpu{}", i))
                     .marker(symbols::Marker::Braille)
                     .graph_type(GraphType::Line)
-                    .style(Style::new().fg(ratatui::style::Color::Indexed(i as u8)))
+                    .style(Style::new().fg(theme::get().series(i)))
                     .data(&x.0)
             })
             .collect(),
+        window,
+        cursor,
     )
     .legend_position(Some(ratatui::widgets::LegendPosition::BottomLeft))
-    .hidden_legend_constraints((
-        Constraint::Ratio(1, data[0].0.len() as u32),
-        Constraint::Ratio(1, data[0].0.len() as u32),
+    .hidden_legend_constraints((Constraint::Ratio(1, samples), Constraint::Ratio(1, samples)))
+    .block(block)
+}
+
+fn throughput_max(data: &[DeviceRates]) -> f64 {
+    data.iter()
+        .flat_map(|x| x.1 .0.iter().chain(x.2 .0.iter()))
+        .map(|x| x.1)
+        .fold(1024.0, f64::max)
+        * 1.1
+}
+
+fn throughput_labels(data: &[DeviceRates]) -> [String; 3] {
+    let max = throughput_max(data);
+    [
+        "0".to_string(),
+        format!("{}/s", get_bytes(max / 2.0)),
+        format!("{}/s", get_bytes(max)),
+    ]
+}
+
+fn label_width(labels: &[String]) -> u16 {
+    labels.iter().map(|x| x.chars().count()).max().unwrap_or(0) as u16
+}
+
+/// Charts a pair of byte rates per device, e.g. received/transmitted per network interface.
+fn throughput<'a>(
+    title: &'a str,
+    labels: [&str; 2],
+    data: &'a [DeviceRates],
+    window: &'a Window,
+    cursor: &'a Cursor,
+) -> Chart<'a> {
+    let block = get_block().title(title);
+    let max = throughput_max(data);
+
+    let mut datasets = Vec::new();
+    for (i, (name, rx, tx)) in data.iter().enumerate() {
+        let style = Style::new().fg(theme::get().series(i));
+        datasets.push(
+            Dataset::default()
+                .name(format!("{} {}", name, labels[0]))
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(style)
+                .data(&rx.0),
+        );
+        datasets.push(
+            Dataset::default()
+                .name(format!("{} {}", name, labels[1]))
+                .marker(symbols::Marker::Dot)
+                .graph_type(GraphType::Line)
+                .style(style)
+                .data(&tx.0),
+        );
+    }
+
+    Chart::new(with_cursor(datasets, cursor))
+        .x_axis(window.axis())
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max])
+                .labels(throughput_labels(data)),
+        )
+        .legend_position(Some(ratatui::widgets::LegendPosition::TopLeft))
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
+        .block(block)
+}
+
+fn disk_busy<'a>(
+    data: &'a [(String, HistoryData)],
+    window: &'a Window,
+    cursor: &'a Cursor,
+) -> Chart<'a> {
+    let block = get_block().title("Disk Busy");
+    default_chart(
+        data.iter()
+            .enumerate()
+            .map(|(i, (name, x))| {
+                Dataset::default()
+                    .name(name.clone())
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::new().fg(theme::get().series(i)))
+                    .data(&x.0)
+            })
+            .collect(),
+        window,
+        cursor,
+    )
+    .legend_position(Some(ratatui::widgets::LegendPosition::TopLeft))
+    .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
+    .block(block)
+}
+
+fn temperature_max(data: &[(String, HistoryData)]) -> f64 {
+    data.iter()
+        .flat_map(|x| x.1 .0.iter())
+        .map(|x| x.1)
+        .fold(100.0, f64::max)
+}
+
+fn temperatures<'a>(
+    data: &'a [(String, HistoryData)],
+    window: &'a Window,
+    cursor: &'a Cursor,
+) -> Chart<'a> {
+    let block = get_block().title("Temperature");
+    let max = temperature_max(data);
+
+    Chart::new(with_cursor(
+        data.iter()
+            .enumerate()
+            .map(|(i, (name, x))| {
+                Dataset::default()
+                    .name(name.clone())
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::new().fg(theme::get().series(i)))
+                    .data(&x.0)
+            })
+            .collect(),
+        cursor,
     ))
+    .x_axis(window.axis())
+    .y_axis(Axis::default().bounds([0.0, max]).labels([
+        "0°C".to_string(),
+        format!("{:.0}°C", max / 2.0),
+        format!("{:.0}°C", max),
+    ]))
+    .legend_position(Some(ratatui::widgets::LegendPosition::TopLeft))
+    .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
     .block(block)
 }
@@ -1,17 +1,88 @@
 use ratatui::{
-    layout::Alignment,
-    style::{Color, Style},
-    widgets::{Block, BorderType},
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::Style,
+    widgets::{Block, BorderType, Widget},
+    Frame,
 };
 
+use crate::{config::Grid, theme};
+
+pub mod alerts;
+pub mod details;
+pub mod help;
 pub mod history;
 pub mod monitor;
 pub mod processes;
+pub mod sensors;
+pub mod signal;
 pub mod stats;
+pub mod summary;
 
 pub fn get_block() -> Block<'static> {
     Block::bordered()
         .title_alignment(Alignment::Center)
-        .title_style(Style::default().fg(Color::Blue))
+        .title_style(Style::default().fg(theme::get().title))
+        .border_style(Style::default().fg(theme::get().border))
         .border_type(BorderType::Rounded)
 }
+
+/// A `width` x `height` rectangle in the middle of `area`, shrunk to fit if needed.
+pub fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Splits `area` into the cells of `grid`, row by row.
+pub fn grid(area: Rect, grid: Grid) -> Vec<Rect> {
+    let vertical = Layout::vertical(vec![Constraint::Ratio(1, grid.rows as u32); grid.rows]);
+    let horizontal = Layout::horizontal(vec![
+        Constraint::Ratio(1, grid.columns as u32);
+        grid.columns
+    ]);
+
+    vertical
+        .split(area)
+        .iter()
+        .flat_map(|row| horizontal.split(*row).to_vec())
+        .collect()
+}
+
+/// The narrowest a card may get before the grid drops a column.
+pub const MIN_CARD_WIDTH: u16 = 40;
+
+/// `layout` with the columns that would be narrower than [`MIN_CARD_WIDTH`] dropped.
+fn fit(area: Rect, layout: Grid) -> Grid {
+    let columns = ((area.width / MIN_CARD_WIDTH) as usize).clamp(1, layout.columns);
+    Grid::new(layout.rows, columns)
+}
+
+/// How many cards fit on one page of `layout`.
+pub fn card_capacity(area: Rect, layout: Grid) -> usize {
+    let grid = fit(area, layout);
+    grid.rows * grid.columns
+}
+
+/// Draws page `page` of `cards` on `layout`, with fewer columns than it asks for when the
+/// cards would be narrower than [`MIN_CARD_WIDTH`]. Returns the number of pages.
+pub fn cards<W: Widget>(
+    frame: &mut Frame,
+    area: Rect,
+    layout: Grid,
+    cards: Vec<W>,
+    page: usize,
+) -> usize {
+    let areas = grid(area, fit(area, layout));
+    let pages = cards.len().div_ceil(areas.len()).max(1);
+    let page = page.min(pages - 1);
+
+    for (widget, area) in cards.into_iter().skip(page * areas.len()).zip(areas) {
+        frame.render_widget(widget, area);
+    }
+    pages
+}
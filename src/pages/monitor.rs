@@ -1,80 +1,350 @@
 use ratatui::{
-    layout::{Constraint, Layout, Rect},
-    style::{Color, Style},
-    widgets::Gauge,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::Style,
+    widgets::{Gauge, LineGauge, Paragraph},
     Frame,
 };
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, ops::Range};
 
 use crate::{
-    data::{Core, CpuInfo, Data, Memory},
-    pages::get_block,
+    alerts::Alerts,
+    config::{self, Grid},
+    data::{battery::Battery, Core, CpuInfo, Data, Memory},
+    keymap::{Action, Keymap, Scope},
+    pages::{card_capacity, cards, get_block, grid},
+    theme,
 };
 
-const Y: usize = 12;
-const X: usize = 3;
+/// How the cores are drawn. `Auto` uses gauges while they all fit on one page.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorMode {
+    #[default]
+    Auto,
+    Gauges,
+    Compact,
+}
 
-pub fn draw(frame: &mut Frame, area: Rect, data: &Data) {
-    let vertical = Layout::vertical([Constraint::Ratio(1, Y as u32); Y]);
-    let horizontal = Layout::horizontal([Constraint::Ratio(1, X as u32); X]);
-    let v = vertical.areas::<Y>(area);
-    let mut areas = Vec::new();
+impl MonitorMode {
+    fn next(&self) -> Self {
+        match self {
+            MonitorMode::Auto => MonitorMode::Gauges,
+            MonitorMode::Gauges => MonitorMode::Compact,
+            MonitorMode::Compact => MonitorMode::Auto,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            MonitorMode::Auto => "auto",
+            MonitorMode::Gauges => "gauges",
+            MonitorMode::Compact => "compact",
+        }
+    }
+}
+
+/// What the cores are grouped by in compact mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreGroup {
+    #[default]
+    None,
+    Socket,
+    Node,
+}
 
-    for a in v {
-        for l in horizontal.areas::<X>(a) {
-            areas.push(l);
+impl CoreGroup {
+    fn next(&self) -> Self {
+        match self {
+            CoreGroup::None => CoreGroup::Socket,
+            CoreGroup::Socket => CoreGroup::Node,
+            CoreGroup::Node => CoreGroup::None,
         }
     }
 
-    let mut widgets = vec![cpu(&data.cpu), memory(&data.memory), swap(&data.memory)];
+    fn name(&self) -> &'static str {
+        match self {
+            CoreGroup::None => "none",
+            CoreGroup::Socket => "socket",
+            CoreGroup::Node => "node",
+        }
+    }
+
+    fn key(&self, core: &Core) -> Option<usize> {
+        match self {
+            CoreGroup::None => None,
+            CoreGroup::Socket => core.socket,
+            CoreGroup::Node => core.node,
+        }
+    }
+
+    fn title(&self, key: Option<usize>) -> String {
+        let id = key.map_or("?".to_string(), |x| x.to_string());
+        match self {
+            CoreGroup::None => "Cores".to_string(),
+            CoreGroup::Socket => format!("Socket {}", id),
+            CoreGroup::Node => format!("Node {}", id),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MonitorView {
+    pub mode: MonitorMode,
+    pub group: CoreGroup,
+}
+
+impl Default for MonitorView {
+    fn default() -> Self {
+        let config = &config::get().monitor;
+        Self {
+            mode: config.mode,
+            group: config.group,
+        }
+    }
+}
+
+impl MonitorView {
+    pub fn next_mode(&mut self) {
+        self.mode = self.mode.next();
+    }
+
+    pub fn next_group(&mut self) {
+        self.group = self.group.next();
+    }
+
+    pub fn status(&self, keymap: &Keymap) -> String {
+        format!(
+            " | Mode <{}> {} | Group <{}> {}",
+            keymap.hint(Scope::Monitor, Action::CycleMode),
+            self.mode.name(),
+            keymap.hint(Scope::Monitor, Action::CycleGroup),
+            self.group.name()
+        )
+    }
+}
+
+/// The narrowest a core's mini-bar gets before a column is dropped.
+const BAR_WIDTH: u16 = 24;
+/// The width of a core's heat-map cell, used when the mini-bars don't fit.
+const HEAT_WIDTH: u16 = 5;
+
+/// Returns the number of pages the gauges, or in compact mode the core groups, are split over.
+pub fn draw(
+    frame: &mut Frame,
+    area: Rect,
+    data: &Data,
+    alerts: &Alerts,
+    view: &MonitorView,
+    page: usize,
+) -> usize {
+    let layout = config::get().layout.monitor;
+
+    let mut widgets = vec![
+        cpu(&data.cpu, alerts),
+        memory(&data.memory, alerts),
+        swap(&data.memory, alerts),
+    ];
+    for b in data.batteries.iter() {
+        widgets.push(battery(b));
+    }
+
+    let compact = match view.mode {
+        MonitorMode::Auto => widgets.len() + data.cpu.cores.len() > card_capacity(area, layout),
+        MonitorMode::Gauges => false,
+        MonitorMode::Compact => true,
+    };
+    if compact {
+        return draw_compact(
+            frame,
+            area,
+            widgets,
+            &data.cpu.cores,
+            alerts,
+            view.group,
+            page,
+        );
+    }
+
     for c in data.cpu.cores.iter() {
-        widgets.push(core(c));
+        widgets.push(core(c, alerts));
     }
+    cards(frame, area, layout, widgets, page)
+}
+
+/// The overall gauges in one row, with every core as a mini-bar or heat-map cell below. Groups
+/// that don't fit below are moved to later pages, split if one is taller than a page. Returns
+/// the number of pages.
+fn draw_compact(
+    frame: &mut Frame,
+    area: Rect,
+    gauges: Vec<Gauge<'static>>,
+    cores: &[Core],
+    alerts: &Alerts,
+    group: CoreGroup,
+    page: usize,
+) -> usize {
+    let [top, bottom] = Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+    let top = Layout::horizontal(vec![
+        Constraint::Ratio(1, gauges.len() as u32);
+        gauges.len()
+    ])
+    .split(top);
+    for (gauge, area) in gauges.into_iter().zip(top.iter()) {
+        frame.render_widget(gauge, *area);
+    }
+
+    let mut groups: BTreeMap<Option<usize>, Vec<&Core>> = BTreeMap::new();
+    for core in cores {
+        groups.entry(group.key(core)).or_default().push(core);
+    }
+
+    // Each group is a bordered block, so its cells get two columns less than the page.
+    let inner = bottom.width.saturating_sub(2);
+    let shape = |n: usize, width: u16| {
+        let columns = ((inner / width) as usize).clamp(1, n.max(1));
+        (n.div_ceil(columns), columns)
+    };
+    let bars_height = groups
+        .values()
+        .map(|x| shape(x.len(), BAR_WIDTH).0 + 2)
+        .sum::<usize>();
+    let heat = bars_height > bottom.height as usize;
+    let width = match heat {
+        true => HEAT_WIDTH,
+        false => BAR_WIDTH,
+    };
+
+    // A group taller than the page is split into blocks of as many rows as fit.
+    let max_rows = (bottom.height as usize).saturating_sub(2).max(1);
+    let mut blocks = Vec::new();
+    for (key, cores) in groups.iter() {
+        let usage = cores.iter().map(|x| x.usage).sum::<f32>() / cores.len() as f32;
+        let (_, columns) = shape(cores.len(), width);
+        for chunk in cores.chunks(max_rows * columns) {
+            let title = format!("{} {:.1}%", group.title(*key), usage);
+            blocks.push((title, chunk, shape(chunk.len(), width)));
+        }
+    }
+
+    let heights = blocks
+        .iter()
+        .map(|(_, _, (rows, _))| rows + 2)
+        .collect::<Vec<usize>>();
+    let pages = paginate(&heights, bottom.height as usize);
+    let shown = pages[page.min(pages.len() - 1)].clone();
+
+    let mut constraints = heights[shown.clone()]
+        .iter()
+        .map(|x| Constraint::Length(*x as u16))
+        .collect::<Vec<Constraint>>();
+    constraints.push(Constraint::Min(0));
+    let areas = Layout::vertical(constraints).split(bottom);
+
+    for ((title, cores, (rows, columns)), area) in blocks.drain(shown).zip(areas.iter()) {
+        let block = get_block().title(title);
+        let cells = grid(block.inner(*area), Grid::new(rows, columns));
+        frame.render_widget(block, *area);
 
-    for (i, widget) in widgets.into_iter().enumerate() {
-        if i < areas.len() {
-            frame.render_widget(widget, areas[i]);
+        for (core, cell) in cores.iter().zip(cells) {
+            match heat {
+                true => frame.render_widget(heat_cell(core), cell),
+                false => frame.render_widget(bar(core, alerts), cell),
+            }
         }
     }
+    pages.len()
 }
 
-fn cpu(cpu: &CpuInfo) -> Gauge<'static> {
+/// Splits blocks of `heights` into pages of at most `height`, in order. A block taller than a
+/// page gets one to itself.
+fn paginate(heights: &[usize], height: usize) -> Vec<Range<usize>> {
+    let mut pages = Vec::new();
+    let (mut start, mut used) = (0, 0);
+    for (i, x) in heights.iter().enumerate() {
+        if i > start && used + x > height {
+            pages.push(start..i);
+            (start, used) = (i, 0);
+        }
+        used += x;
+    }
+    pages.push(start..heights.len());
+    pages
+}
+
+fn bar(core: &Core, alerts: &Alerts) -> LineGauge<'static> {
+    LineGauge::default()
+        .ratio((core.usage as f64 / 100.0).clamp(0.0, 1.0))
+        .label(format!("{:>6} {:>5.1}%", core.name, core.usage))
+        .filled_style(alerts.level(&core.name).style(theme::get().core_gauge))
+}
+
+/// Green while idle through to red when saturated.
+fn heat_cell(core: &Core) -> Paragraph<'static> {
+    let theme = theme::get();
+    Paragraph::new(format!("{:.0}", core.usage))
+        .alignment(Alignment::Center)
+        .style(
+            Style::default()
+                .bg(theme.heat(core.usage as f64))
+                .fg(theme.heat_text),
+        )
+}
+
+fn cpu(cpu: &CpuInfo, alerts: &Alerts) -> Gauge<'static> {
     let block = get_block().title("CPU");
     Gauge::default()
         .percent(cpu.usage as u16)
         .label(format!("{:.2}%", cpu.usage))
         .block(block)
-        .gauge_style(Style::default().fg(Color::Green))
+        .gauge_style(alerts.level("CPU").style(theme::get().gauge))
 }
 
-fn memory(mem: &Memory) -> Gauge<'static> {
+fn memory(mem: &Memory, alerts: &Alerts) -> Gauge<'static> {
     let block = get_block().title("Memory");
     Gauge::default()
-        .percent((mem.used_mem as f64 / mem.total_mem as f64 * 100.0) as u16)
-        .label(format!(
-            "{:.2}%",
-            (mem.used_mem as f64 / mem.total_mem as f64 * 100.0)
-        ))
+        .percent(mem.mem_percent() as u16)
+        .label(format!("{:.2}%", mem.mem_percent()))
         .block(block)
-        .gauge_style(Style::default().fg(Color::Green))
+        .gauge_style(alerts.level("Memory").style(theme::get().gauge))
 }
 
-fn swap(mem: &Memory) -> Gauge<'static> {
+fn swap(mem: &Memory, alerts: &Alerts) -> Gauge<'static> {
     let block = get_block().title("Swap");
     Gauge::default()
-        .percent((mem.used_swap as f64 / mem.total_swap as f64 * 100.0) as u16)
-        .label(format!(
-            "{:.2}%",
-            (mem.used_swap as f64 / mem.total_swap as f64 * 100.0)
-        ))
+        .percent(mem.swap_percent() as u16)
+        .label(format!("{:.2}%", mem.swap_percent()))
         .block(block)
-        .gauge_style(Style::default().fg(Color::Green))
+        .gauge_style(alerts.level("Swap").style(theme::get().gauge))
 }
 
-fn core(core: &Core) -> Gauge<'static> {
-    let block = get_block().title(format!("{}", core.name));
+fn battery(battery: &Battery) -> Gauge<'static> {
+    let block = get_block().title(format!("Battery {}", battery.name));
+    Gauge::default()
+        .percent(battery.percent as u16)
+        .label(format!("{:.0}% {}", battery.percent, battery.state))
+        .block(block)
+        .gauge_style(Style::default().fg(theme::get().gauge))
+}
+
+fn core(core: &Core, alerts: &Alerts) -> Gauge<'static> {
+    let block = get_block().title(core.name.clone());
     Gauge::default()
         .percent(core.usage as u16)
         .label(format!("{:.2}%", core.usage))
         .block(block)
-        .gauge_style(Style::default().fg(Color::Blue))
+        .gauge_style(alerts.level(&core.name).style(theme::get().core_gauge))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_that_overflow_go_to_the_next_page() {
+        assert_eq!(paginate(&[4, 4, 2], 10).len(), 1);
+        assert_eq!(paginate(&[4, 4, 4, 6], 10), [0..2, 2..4]);
+        assert_eq!(paginate(&[4, 12, 3], 10), [0..1, 1..2, 2..3]);
+    }
 }
@@ -1,47 +1,694 @@
-use crate::{data::Process, get_time};
+use crate::{config, data::Process, get_bytes, get_time, theme};
 use ratatui::{
-    layout::{Constraint::Fill, Rect},
-    style::{Style, Stylize},
+    layout::{
+        Constraint::{self, Fill},
+        Layout, Rect,
+    },
     widgets::{HighlightSpacing, Row, Table, TableState},
     Frame,
 };
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
+use sysinfo::Pid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortColumn {
+    Pid,
+    User,
+    Memory,
+    Cpu,
+    MemPercent,
+    DiskRead,
+    DiskWrite,
+    Time,
+    Command,
+}
+
+impl SortColumn {
+    const ALL: [SortColumn; 9] = [
+        SortColumn::Pid,
+        SortColumn::User,
+        SortColumn::Memory,
+        SortColumn::Cpu,
+        SortColumn::MemPercent,
+        SortColumn::DiskRead,
+        SortColumn::DiskWrite,
+        SortColumn::Time,
+        SortColumn::Command,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            SortColumn::Pid => "PID",
+            SortColumn::User => "USER",
+            SortColumn::Memory => "MEM (Mb)",
+            SortColumn::Cpu => "CPU%",
+            SortColumn::MemPercent => "MEM%",
+            SortColumn::DiskRead => "DISK R/s",
+            SortColumn::DiskWrite => "DISK W/s",
+            SortColumn::Time => "TIME",
+            SortColumn::Command => "COMMAND",
+        }
+    }
+
+    fn is_disk(&self) -> bool {
+        matches!(self, SortColumn::DiskRead | SortColumn::DiskWrite)
+    }
+
+    fn width(&self) -> Constraint {
+        match self {
+            SortColumn::Pid | SortColumn::Cpu | SortColumn::MemPercent => Fill(1),
+            SortColumn::Command => Fill(7),
+            _ => Fill(2),
+        }
+    }
+
+    fn compare(&self, a: &Process, b: &Process) -> Ordering {
+        match self {
+            SortColumn::Pid => a.pid.cmp(&b.pid),
+            SortColumn::User => a.user.cmp(&b.user),
+            SortColumn::Memory | SortColumn::MemPercent => a.memory.cmp(&b.memory),
+            SortColumn::Cpu => a.cpu.total_cmp(&b.cpu),
+            SortColumn::DiskRead => a.disk_read.total_cmp(&b.disk_read),
+            SortColumn::DiskWrite => a.disk_write.total_cmp(&b.disk_write),
+            SortColumn::Time => a.run_time.cmp(&b.run_time),
+            SortColumn::Command => a.command.cmp(&b.command),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ProcessSort {
+    pub column: SortColumn,
+    pub descending: bool,
+    /// Whether the DISK R/W columns are shown.
+    pub disk_columns: bool,
+}
+
+impl Default for ProcessSort {
+    fn default() -> Self {
+        let config = &config::get().processes;
+        Self {
+            column: config.sort,
+            descending: config.descending,
+            disk_columns: config.disk_columns || config.sort.is_disk(),
+        }
+    }
+}
+
+impl ProcessSort {
+    /// The columns shown in the table, in order.
+    fn columns(&self) -> Vec<SortColumn> {
+        SortColumn::ALL
+            .into_iter()
+            .filter(|x| self.disk_columns || !x.is_disk())
+            .collect()
+    }
+
+    fn index(&self) -> usize {
+        self.columns()
+            .iter()
+            .position(|x| x == &self.column)
+            .unwrap_or_default()
+    }
+
+    pub fn next(&mut self) {
+        let columns = self.columns();
+        self.column = columns[(self.index() + 1) % columns.len()];
+    }
+
+    pub fn previous(&mut self) {
+        let columns = self.columns();
+        self.column = columns[(self.index() + columns.len() - 1) % columns.len()];
+    }
+
+    /// Shows or hides the DISK R/W columns, sorting by CPU if the sorted column is hidden.
+    pub fn toggle_disk_columns(&mut self) {
+        self.disk_columns = !self.disk_columns;
+        if !self.disk_columns && self.column.is_disk() {
+            self.column = SortColumn::Cpu;
+        }
+    }
+
+    pub fn reverse(&mut self) {
+        self.descending = !self.descending;
+    }
+
+    /// Sorts by `column`, or reverses the order if it is already sorted by it.
+    pub fn sort_by(&mut self, column: SortColumn) {
+        match column == self.column {
+            true => self.reverse(),
+            false => self.column = column,
+        }
+    }
+
+    /// The column under `x` in a table drawn in `area`, laid out the same way the table lays
+    /// out its columns after the `>>` highlight symbol.
+    pub fn column_at(&self, area: Rect, x: u16) -> Option<SortColumn> {
+        let columns = self.columns();
+        let [_, area] =
+            Layout::horizontal([Constraint::Length(HIGHLIGHT.len() as u16), Fill(0)]).areas(area);
+        let rects = Layout::horizontal(columns.iter().map(|x| x.width()))
+            .spacing(1)
+            .split(area);
+        columns
+            .into_iter()
+            .zip(rects.iter())
+            .find(|(_, rect)| x >= rect.left() && x < rect.right())
+            .map(|(column, _)| column)
+    }
+
+    /// Returns the processes in the order they are displayed, ties are broken by PID so rows
+    /// with equal keys don't swap places between samples.
+    pub fn apply<'a>(&self, processes: &'a [Process]) -> Vec<&'a Process> {
+        let mut sorted = processes.iter().collect::<Vec<&Process>>();
+        sorted.sort_by(|a, b| {
+            let order = self.column.compare(a, b).then_with(|| a.pid.cmp(&b.pid));
+            match self.descending {
+                true => order.reverse(),
+                false => order,
+            }
+        });
+        sorted
+    }
+
+    fn header(&self) -> Row<'static> {
+        Row::new(
+            self.columns()
+                .iter()
+                .map(|x| match (x == &self.column, self.descending) {
+                    (true, true) => format!("{} ▼", x.title()),
+                    (true, false) => format!("{} ▲", x.title()),
+                    (false, _) => x.title().to_string(),
+                })
+                .collect::<Vec<String>>(),
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ProcessFilter {
+    pub query: String,
+    pub regex: bool,
+    pub invert: bool,
+    pub editing: bool,
+}
+
+enum Matcher {
+    Text(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn is_match(&self, value: &str) -> bool {
+        match self {
+            Matcher::Text(text) => value.to_lowercase().contains(text),
+            Matcher::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+impl ProcessFilter {
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.editing = false;
+    }
+
+    /// Invalid regular expressions fall back to a plain text search, so a half typed pattern
+    /// doesn't empty the table.
+    fn matcher(&self) -> Matcher {
+        if self.regex {
+            if let Ok(regex) = RegexBuilder::new(&self.query)
+                .case_insensitive(true)
+                .build()
+            {
+                return Matcher::Regex(regex);
+            }
+        }
+        Matcher::Text(self.query.to_lowercase())
+    }
+
+    pub fn is_valid(&self) -> bool {
+        !self.regex || Regex::new(&self.query).is_ok()
+    }
+
+    pub fn apply<'a>(&self, processes: Vec<&'a Process>) -> Vec<&'a Process> {
+        if !self.is_active() {
+            return processes;
+        }
+
+        let matcher = self.matcher();
+        processes
+            .into_iter()
+            .filter(|p| {
+                let found = matcher.is_match(&p.name.to_string_lossy())
+                    || matcher.is_match(&p.command)
+                    || matcher.is_match(&p.user)
+                    || matcher.is_match(&p.pid.to_string());
+                found != self.invert
+            })
+            .collect()
+    }
+
+    pub fn status(&self, matched: usize, total: usize) -> String {
+        let mut status = format!(" | Filter: {}", self.query);
+        if self.editing {
+            status.push('_');
+        }
+        if self.regex {
+            status.push_str(match self.is_valid() {
+                true => " [regex]",
+                false => " [invalid regex]",
+            });
+        }
+        if self.invert {
+            status.push_str(" [inverted]");
+        }
+        status.push_str(&format!(" ({}/{})", matched, total));
+        status
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ProcessTree {
+    pub enabled: bool,
+    pub collapsed: HashSet<Pid>,
+}
+
+impl ProcessTree {
+    pub fn toggle(&mut self, pid: Pid) {
+        if !self.collapsed.remove(&pid) {
+            self.collapsed.insert(pid);
+        }
+    }
+
+    /// Lays the processes out depth first, keeping the sort order between siblings. A process
+    /// whose parent was filtered out is shown as a root.
+    fn apply<'a>(&self, processes: Vec<&'a Process>) -> Vec<ProcessRow<'a>> {
+        let pids = processes.iter().map(|p| p.pid).collect::<HashSet<Pid>>();
+        let mut roots = Vec::new();
+        let mut children: HashMap<Pid, Vec<&Process>> = HashMap::new();
+        for p in processes {
+            match p.parent.filter(|x| pids.contains(x) && x != &p.pid) {
+                Some(parent) => children.entry(parent).or_default().push(p),
+                None => roots.push(p),
+            }
+        }
 
-pub fn draw(frame: &mut Frame, area: Rect, table: &mut TableState, processes: &Vec<Process>) {
+        let mut rows = Vec::new();
+        let mut visited = HashSet::new();
+        for p in roots {
+            self.push(&mut rows, &children, &mut visited, p, 0);
+        }
+        rows
+    }
+
+    fn push<'a>(
+        &self,
+        rows: &mut Vec<ProcessRow<'a>>,
+        children: &HashMap<Pid, Vec<&'a Process>>,
+        visited: &mut HashSet<Pid>,
+        process: &'a Process,
+        depth: usize,
+    ) {
+        if !visited.insert(process.pid) {
+            return;
+        }
+        let kids = children
+            .get(&process.pid)
+            .map(|x| x.as_slice())
+            .unwrap_or(&[]);
+        let collapsed = !kids.is_empty() && self.collapsed.contains(&process.pid);
+        let (cpu, memory) = match collapsed {
+            true => subtree_usage(children, process),
+            false => (process.cpu, process.memory),
+        };
+
+        rows.push(ProcessRow {
+            process,
+            depth,
+            children: kids.len(),
+            collapsed,
+            cpu,
+            memory,
+        });
+
+        if !collapsed {
+            for kid in kids {
+                self.push(rows, children, visited, kid, depth + 1);
+            }
+        }
+    }
+}
+
+fn subtree_usage(children: &HashMap<Pid, Vec<&Process>>, process: &Process) -> (f32, u64) {
+    let mut stack = vec![process];
+    let mut seen = HashSet::new();
+    let (mut cpu, mut memory) = (0.0, 0);
+    while let Some(p) = stack.pop() {
+        if !seen.insert(p.pid) {
+            continue;
+        }
+        cpu += p.cpu;
+        memory += p.memory;
+        if let Some(kids) = children.get(&p.pid) {
+            stack.extend(kids.iter());
+        }
+    }
+    (cpu, memory)
+}
+
+/// A row of the process table. In tree mode a collapsed parent carries the summed usage of
+/// its whole subtree.
+#[derive(Clone, Debug)]
+pub struct ProcessRow<'a> {
+    pub process: &'a Process,
+    pub depth: usize,
+    pub children: usize,
+    pub collapsed: bool,
+    pub cpu: f32,
+    pub memory: u64,
+}
+
+impl<'a> From<&'a Process> for ProcessRow<'a> {
+    fn from(value: &'a Process) -> Self {
+        Self {
+            process: value,
+            depth: 0,
+            children: 0,
+            collapsed: false,
+            cpu: value.cpu,
+            memory: value.memory,
+        }
+    }
+}
+
+/// The process highlighted in the table. It is tracked by PID rather than by row so that it
+/// stays on the same process when the rows are re-sorted by a new sample.
+#[derive(Clone, Debug)]
+pub struct Selected {
+    pub pid: Pid,
+    pub name: String,
+    pub gone: bool,
+}
+
+impl From<&Process> for Selected {
+    fn from(value: &Process) -> Self {
+        Self {
+            pid: value.pid,
+            name: value.name.to_string_lossy().to_string(),
+            gone: false,
+        }
+    }
+}
+
+impl Selected {
+    /// Finds the row of the selected process, marking it as gone if it is no longer running.
+    pub fn resolve(&mut self, processes: &[Process], rows: &[ProcessRow]) -> Option<usize> {
+        self.gone = !processes.iter().any(|x| x.pid == self.pid);
+        rows.iter().position(|x| x.process.pid == self.pid)
+    }
+
+    pub fn status(&self) -> String {
+        match self.gone {
+            true => format!(" | ✗ Process gone: {} ({})", self.pid, self.name),
+            false => format!(" | Selected: {} ({})", self.pid, self.name),
+        }
+    }
+}
+
+/// The rows shown in the table, in display order.
+pub fn visible<'a>(
+    processes: &'a [Process],
+    sort: &ProcessSort,
+    filter: &ProcessFilter,
+    tree: &ProcessTree,
+) -> Vec<ProcessRow<'a>> {
+    let processes = filter.apply(sort.apply(processes));
+    match tree.enabled {
+        true => tree.apply(processes),
+        false => processes.into_iter().map(|x| x.into()).collect(),
+    }
+}
+
+/// The symbol in front of the selected row.
+const HIGHLIGHT: &str = ">>";
+
+/// The row of `processes` under `y` in a table drawn in `area`, `None` on the header.
+pub fn row_at(area: Rect, table: &TableState, y: u16) -> Option<usize> {
+    match y > area.top() && y < area.bottom() {
+        true => Some(table.offset() + (y - area.top() - 1) as usize),
+        false => None,
+    }
+}
+
+pub fn draw(
+    frame: &mut Frame,
+    area: Rect,
+    table: &mut TableState,
+    processes: &[ProcessRow],
+    sort: &ProcessSort,
+) {
+    let columns = sort.columns();
     let mut rows = Vec::new();
     for p in processes {
-        rows.push(process(p));
+        rows.push(process(p, &columns));
     }
 
-    let widths = [
-        Fill(1),
-        Fill(2),
-        Fill(2),
-        Fill(1),
-        Fill(1),
-        Fill(2),
-        Fill(7),
-    ];
+    let widths = columns
+        .iter()
+        .map(|x| x.width())
+        .collect::<Vec<Constraint>>();
     frame.render_stateful_widget(
         Table::new(rows, widths)
             .highlight_spacing(HighlightSpacing::Always)
-            .highlight_symbol(">>")
-            .row_highlight_style(Style::new().on_blue())
-            .header(Row::new(vec![
-                "PID", "USER", "MEM (Mb)", "CPU%", "MEM%", "TIME", "COMMAND",
-            ])),
+            .highlight_symbol(HIGHLIGHT)
+            .row_highlight_style(theme::get().highlight_style())
+            .header(sort.header()),
         area,
         table,
     );
 }
 
-fn process<'a>(process: &'a Process) -> Row<'a> {
-    Row::new(vec![
-        process.pid.to_string(),
-        process.user.clone(),
-        format!("{:.1}", process.memory as f64 / 1024_f64 / 1024_f64),
-        format!("{:.1}", process.cpu),
-        format!("{:.1}", process.memory as f64 / process.total_m as f64),
-        get_time(process.run_time),
-        process.command.clone(),
-    ])
+fn process<'a>(row: &ProcessRow<'a>, columns: &[SortColumn]) -> Row<'a> {
+    let process = row.process;
+    let branch = match (row.children, row.collapsed) {
+        (0, _) => "",
+        (_, true) => "▸ ",
+        (_, false) => "▾ ",
+    };
+    Row::new(
+        columns
+            .iter()
+            .map(|x| match x {
+                SortColumn::Pid => process.pid.to_string(),
+                SortColumn::User => process.user.clone(),
+                SortColumn::Memory => format!("{:.1}", row.memory as f64 / 1024_f64 / 1024_f64),
+                SortColumn::Cpu => format!("{:.1}", row.cpu),
+                SortColumn::MemPercent => {
+                    format!("{:.1}", row.memory as f64 / process.total_m as f64)
+                }
+                SortColumn::DiskRead => get_bytes(process.disk_read),
+                SortColumn::DiskWrite => get_bytes(process.disk_write),
+                SortColumn::Time => get_time(process.run_time),
+                SortColumn::Command => {
+                    format!("{}{}{}", "  ".repeat(row.depth), branch, process.command)
+                }
+            })
+            .collect::<Vec<String>>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, parent: Option<u32>, cpu: f32, command: &str) -> Process {
+        Process {
+            user: "root".to_string(),
+            name: command.into(),
+            pid: Pid::from_u32(pid),
+            parent: parent.map(Pid::from_u32),
+            memory: pid as u64 * 1024,
+            cpu,
+            command: command.to_string(),
+            run_time: 0,
+            total_m: 0,
+            disk_read: 0.0,
+            disk_write: 0.0,
+        }
+    }
+
+    fn pids(processes: &[&Process]) -> Vec<u32> {
+        processes.iter().map(|x| x.pid.as_u32()).collect()
+    }
+
+    fn sort(column: SortColumn, descending: bool) -> ProcessSort {
+        ProcessSort {
+            column,
+            descending,
+            disk_columns: false,
+        }
+    }
+
+    #[test]
+    fn sorts_by_the_column_with_ties_broken_by_pid() {
+        let processes = [
+            process(3, None, 5.0, "bash"),
+            process(1, None, 20.0, "init"),
+            process(4, None, 5.0, "vim"),
+            process(2, None, 5.0, "ssh"),
+        ];
+        let ascending = sort(SortColumn::Cpu, false);
+        assert_eq!(pids(&ascending.apply(&processes)), [2, 3, 4, 1]);
+        let descending = sort(SortColumn::Cpu, true);
+        assert_eq!(pids(&descending.apply(&processes)), [1, 4, 3, 2]);
+        let command = sort(SortColumn::Command, false);
+        assert_eq!(pids(&command.apply(&processes)), [3, 1, 2, 4]);
+
+        // The same processes in another order come out the same.
+        let mut shuffled = processes.clone();
+        shuffled.reverse();
+        assert_eq!(pids(&descending.apply(&shuffled)), [1, 4, 3, 2]);
+    }
+
+    #[test]
+    fn sorting_by_the_sorted_column_reverses_it() {
+        let mut sort = sort(SortColumn::Cpu, true);
+        sort.sort_by(SortColumn::Cpu);
+        assert!(!sort.descending);
+        sort.sort_by(SortColumn::Pid);
+        assert_eq!(sort.column, SortColumn::Pid);
+        assert!(!sort.descending);
+    }
+
+    #[test]
+    fn hiding_the_disk_columns_moves_the_sort_off_them() {
+        let mut sort = ProcessSort {
+            column: SortColumn::DiskRead,
+            descending: true,
+            disk_columns: true,
+        };
+        sort.toggle_disk_columns();
+        assert_eq!(sort.column, SortColumn::Cpu);
+        sort.column = SortColumn::MemPercent;
+        sort.next();
+        assert_eq!(sort.column, SortColumn::Time);
+    }
+
+    fn filter(query: &str, regex: bool, invert: bool) -> ProcessFilter {
+        ProcessFilter {
+            query: query.to_string(),
+            regex,
+            invert,
+            editing: false,
+        }
+    }
+
+    #[test]
+    fn filters_match_text_or_regex_and_can_be_inverted() {
+        let processes = [
+            process(1, None, 0.0, "init"),
+            process(20, None, 0.0, "sshd"),
+            process(31, None, 0.0, "SSH-agent"),
+        ];
+        let all = || processes.iter().collect::<Vec<&Process>>();
+        assert_eq!(pids(&filter("", false, false).apply(all())), [1, 20, 31]);
+        assert_eq!(pids(&filter("ssh", false, false).apply(all())), [20, 31]);
+        assert_eq!(pids(&filter("ssh", false, true).apply(all())), [1]);
+        assert_eq!(pids(&filter("^ssh", true, false).apply(all())), [20, 31]);
+        assert_eq!(pids(&filter("d$", true, true).apply(all())), [1, 31]);
+        // PIDs are matched too.
+        assert_eq!(pids(&filter("20", false, false).apply(all())), [20]);
+    }
+
+    #[test]
+    fn invalid_regexes_fall_back_to_text() {
+        let processes = [process(1, None, 0.0, "init"), process(2, None, 0.0, "a(b")];
+        let all = || processes.iter().collect::<Vec<&Process>>();
+        let invalid = filter("a(", true, false);
+        assert!(!invalid.is_valid());
+        assert!(invalid.status(1, 2).contains("[invalid regex]"));
+        assert_eq!(pids(&invalid.apply(all())), [2]);
+        assert!(filter("a(", false, false).is_valid());
+        assert!(filter("a\\(", true, false).is_valid());
+    }
+
+    fn tree() -> Vec<Process> {
+        vec![
+            process(1, None, 1.0, "init"),
+            process(2, Some(1), 2.0, "sshd"),
+            process(3, Some(2), 4.0, "bash"),
+            process(4, Some(3), 8.0, "vim"),
+            process(5, Some(99), 16.0, "orphan"),
+            process(6, Some(1), 32.0, "cron"),
+            process(7, Some(7), 64.0, "self"),
+        ]
+    }
+
+    fn layout(rows: &[ProcessRow]) -> Vec<(u32, usize)> {
+        rows.iter()
+            .map(|x| (x.process.pid.as_u32(), x.depth))
+            .collect()
+    }
+
+    #[test]
+    fn trees_keep_the_sort_order_and_show_orphans_as_roots() {
+        let processes = tree();
+        let mut view = ProcessTree {
+            enabled: true,
+            ..Default::default()
+        };
+        let sort = sort(SortColumn::Cpu, true);
+        let rows = visible(&processes, &sort, &ProcessFilter::default(), &view);
+        assert_eq!(
+            layout(&rows),
+            [(7, 0), (5, 0), (1, 0), (6, 1), (2, 1), (3, 2), (4, 3)]
+        );
+        assert_eq!(rows[2].children, 2);
+
+        // Without sshd, bash has no parent left and becomes a root.
+        let rows = visible(&processes, &sort, &filter("^s", true, true), &view);
+        assert_eq!(layout(&rows), [(5, 0), (3, 0), (4, 1), (1, 0), (6, 1)]);
+
+        view.enabled = false;
+        let rows = visible(&processes, &sort, &ProcessFilter::default(), &view);
+        assert!(rows.iter().all(|x| x.depth == 0));
+    }
+
+    #[test]
+    fn collapsed_processes_hide_and_sum_their_subtree() {
+        let processes = tree();
+        let mut view = ProcessTree {
+            enabled: true,
+            ..Default::default()
+        };
+        let sort = sort(SortColumn::Pid, false);
+        view.toggle(Pid::from_u32(2));
+        let rows = visible(&processes, &sort, &ProcessFilter::default(), &view);
+        assert_eq!(layout(&rows), [(1, 0), (2, 1), (6, 1), (5, 0), (7, 0)]);
+        assert!(rows[1].collapsed);
+        assert_eq!(rows[1].cpu, 14.0);
+        assert_eq!(rows[1].memory, (2 + 3 + 4) * 1024);
+
+        // A process without children can't be collapsed.
+        view.toggle(Pid::from_u32(6));
+        let rows = visible(&processes, &sort, &ProcessFilter::default(), &view);
+        assert!(!rows[2].collapsed);
+        assert_eq!(rows[2].cpu, 32.0);
+
+        view.toggle(Pid::from_u32(2));
+        let rows = visible(&processes, &sort, &ProcessFilter::default(), &view);
+        assert_eq!(rows.len(), 7);
+        assert_eq!(rows[1].cpu, 2.0);
+    }
 }
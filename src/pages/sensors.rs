@@ -0,0 +1,99 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::Color,
+    widgets::{Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::{
+    alerts::{Alerts, Level},
+    data::sensors::{Sensor, SensorKind},
+    pages::get_block,
+};
+
+pub fn draw(frame: &mut Frame, area: Rect, sensors: &[Sensor], alerts: &Alerts) {
+    let groups = [
+        (SensorKind::Temperature, "Temperatures"),
+        (SensorKind::Fan, "Fans"),
+        (SensorKind::Voltage, "Voltages"),
+    ]
+    .into_iter()
+    .map(|(kind, title)| {
+        let sensors = sensors
+            .iter()
+            .filter(|x| x.kind == kind)
+            .collect::<Vec<&Sensor>>();
+        (title, sensors)
+    })
+    .filter(|(_, sensors)| !sensors.is_empty())
+    .collect::<Vec<(&str, Vec<&Sensor>)>>();
+
+    if groups.is_empty() {
+        let block = get_block().title("Sensors");
+        frame.render_widget(
+            Paragraph::new("No sensors found")
+                .alignment(Alignment::Center)
+                .block(block),
+            area,
+        );
+        return;
+    }
+
+    // Each table gets its rows plus the borders and header.
+    let areas = Layout::vertical(
+        groups
+            .iter()
+            .map(|(_, sensors)| Constraint::Max(sensors.len() as u16 + 3)),
+    )
+    .split(area);
+
+    for ((title, sensors), area) in groups.into_iter().zip(areas.iter()) {
+        frame.render_widget(table(title, &sensors, alerts), *area);
+    }
+}
+
+fn value(value: Option<f64>, kind: SensorKind) -> String {
+    match (value, kind) {
+        (None, _) => "-".to_string(),
+        (Some(x), SensorKind::Fan) => format!("{:.0} {}", x, kind.unit()),
+        (Some(x), SensorKind::Voltage) => format!("{:.3} {}", x, kind.unit()),
+        (Some(x), SensorKind::Temperature) => format!("{:.1} {}", x, kind.unit()),
+    }
+}
+
+fn table<'a>(title: &'a str, sensors: &[&Sensor], alerts: &Alerts) -> Table<'a> {
+    let rows = sensors.iter().map(|x| {
+        // Whichever is worse of the chip's own limits and, for temperatures, the alert.
+        let chip = match (x.critical, x.max) {
+            (Some(critical), _) if x.value >= critical => Level::Critical,
+            (_, Some(max)) if x.value >= max => Level::Warning,
+            _ => Level::Normal,
+        };
+        let configured = match x.kind {
+            SensorKind::Temperature => alerts.level(&x.name()),
+            _ => Level::Normal,
+        };
+        let style = chip.max(configured).style(Color::Reset);
+        Row::new(vec![
+            Cell::from(x.chip.clone()),
+            Cell::from(x.label.clone()),
+            Cell::from(value(Some(x.value), x.kind)),
+            Cell::from(value(x.max, x.kind)),
+            Cell::from(value(x.critical, x.kind)),
+        ])
+        .style(style)
+    });
+
+    let widths = [
+        Constraint::Fill(2),
+        Constraint::Fill(3),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+    ];
+    Table::new(rows, widths)
+        .header(Row::new(vec![
+            "CHIP", "SENSOR", "CURRENT", "MAX", "CRITICAL",
+        ]))
+        .block(get_block().title(title))
+}
@@ -0,0 +1,163 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Clear, HighlightSpacing, List, ListState, Paragraph, Wrap},
+    Frame,
+};
+use std::io;
+use sysinfo::Pid;
+
+use crate::{
+    data::signal::{self, SIGNALS},
+    pages::{centered, get_block},
+    theme,
+};
+
+#[derive(Clone, Debug)]
+pub enum Stage {
+    Pick,
+    Confirm,
+    Done(Result<(), String>),
+}
+
+/// The popup that sends a signal to a single process. The process is told apart from a
+/// later one given the same PID by its start time and name.
+#[derive(Clone, Debug)]
+pub struct SignalPicker {
+    pub pid: Pid,
+    pub name: String,
+    start_time: u64,
+    pub selected: usize,
+    pub stage: Stage,
+}
+
+impl SignalPicker {
+    /// A picker for `pid`, or `None` if it has already exited.
+    pub fn new(pid: Pid) -> Option<Self> {
+        let (start_time, name) = signal::identity(pid)?;
+        Some(Self {
+            pid,
+            name,
+            start_time,
+            selected: 0,
+            stage: Stage::Pick,
+        })
+    }
+
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % SIGNALS.len();
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = (self.selected + SIGNALS.len() - 1) % SIGNALS.len();
+    }
+
+    /// Sends the selected signal, unless the process exited or its PID went to another
+    /// process while the picker was open.
+    pub fn send(&mut self) {
+        let result = match signal::identity(self.pid) {
+            Some(x) if x == (self.start_time, self.name.clone()) => {
+                signal::send(self.pid, SIGNALS[self.selected]).map_err(|e| match e.kind() {
+                    io::ErrorKind::PermissionDenied => "Permission denied".to_string(),
+                    _ => e.to_string(),
+                })
+            }
+            Some(_) => Err("The PID now belongs to another process".to_string()),
+            None => Err("The process has exited".to_string()),
+        };
+        self.stage = Stage::Done(result);
+    }
+}
+
+/// Draws the picker, with `hints` for the keys of the confirmation and result.
+pub fn draw(frame: &mut Frame, area: Rect, picker: &SignalPicker, hints: &str) {
+    let title = format!("Signal {} ({})", picker.pid, picker.name);
+    let signal = signal::name(SIGNALS[picker.selected]);
+    let area = centered(area, 40, SIGNALS.len() as u16 + 2);
+    frame.render_widget(Clear, area);
+
+    match &picker.stage {
+        Stage::Pick => {
+            let list = List::new(SIGNALS.iter().map(|x| signal::name(*x)))
+                .block(get_block().title(title))
+                .highlight_spacing(HighlightSpacing::Always)
+                .highlight_symbol(">>")
+                .highlight_style(theme::get().highlight_style());
+            let mut state = ListState::default().with_selected(Some(picker.selected));
+            frame.render_stateful_widget(list, area, &mut state);
+        }
+        Stage::Confirm => frame.render_widget(
+            message(
+                title,
+                vec![
+                    Line::from(format!("Send {} to {}?", signal, picker.pid)),
+                    Line::from(""),
+                    Line::from(hints.to_string()),
+                ],
+            ),
+            area,
+        ),
+        Stage::Done(Ok(())) => frame.render_widget(
+            message(
+                title,
+                vec![
+                    Line::from(format!("Sent {} to {}", signal, picker.pid)).fg(theme::get().good),
+                    Line::from(""),
+                    Line::from(hints.to_string()),
+                ],
+            ),
+            area,
+        ),
+        Stage::Done(Err(e)) => frame.render_widget(
+            message(
+                title,
+                vec![
+                    Line::from(format!("Failed to send {}: {}", signal, e))
+                        .style(theme::get().critical_style()),
+                    Line::from(""),
+                    Line::from(hints.to_string()),
+                ],
+            ),
+            area,
+        ),
+    }
+}
+
+fn message<'a>(title: String, lines: Vec<Line<'a>>) -> Paragraph<'a> {
+    Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(get_block().title(title))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    #[test]
+    fn refuses_a_reused_pid() {
+        let mut picker = SignalPicker::new(Pid::from_u32(process::id())).unwrap();
+        // As if the process exited and its PID went to one started later.
+        picker.start_time -= 1;
+        picker.send();
+        assert!(matches!(
+            picker.stage,
+            Stage::Done(Err(ref e)) if e == "The PID now belongs to another process"
+        ));
+    }
+
+    #[test]
+    fn refuses_a_renamed_process() {
+        let mut picker = SignalPicker::new(Pid::from_u32(process::id())).unwrap();
+        picker.name.push('x');
+        picker.send();
+        assert!(matches!(picker.stage, Stage::Done(Err(_))));
+    }
+
+    #[test]
+    fn no_picker_for_an_exited_process() {
+        assert!(SignalPicker::new(Pid::from_u32(u32::MAX / 2)).is_none());
+    }
+}
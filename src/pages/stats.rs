@@ -1,81 +1,76 @@
-use hw_linux::{environment::packages::PackageManagers, InfoTrait};
 use ratatui::{
-    layout::{Constraint, Layout, Rect},
-    widgets::{Row, Table},
+    layout::{Constraint, Rect},
+    style::Color,
+    text::Line,
+    widgets::{Cell, Row, Table},
     Frame,
 };
 
 use crate::{
-    data::{CpuInfo, Data, Disk, Memory, Network},
-    get_time,
-    pages::get_block,
+    alerts::Alerts,
+    config,
+    data::{
+        battery::Battery,
+        diskstats::DiskIo,
+        host::{self, Host, Packages},
+        CpuInfo, Data, Disk, Memory, Network,
+    },
+    get_bytes, get_time,
+    pages::{cards, get_block},
 };
 
-const Y: usize = 6;
-const X_1: usize = 1;
-const X_2: usize = 2;
-
-pub fn draw_page_1(frame: &mut Frame, area: Rect, data: &Data, pms: &PackageManagers) {
-    let is_linux = hw_linux::is_linux().unwrap_or(false);
-    let vertical = Layout::vertical([Constraint::Ratio(1, Y as u32); Y]);
-    let horizontal = Layout::horizontal([Constraint::Ratio(1, X_1 as u32); X_1]);
-    let v = vertical.areas::<Y>(area);
-    let mut areas = Vec::new();
-    for a in v {
-        for l in horizontal.areas::<X_1>(a) {
-            areas.push(l);
-        }
-    }
-
+/// Returns the number of pages the cards are split over.
+pub fn draw_page_1(frame: &mut Frame, area: Rect, data: &Data, host: &Host, page: usize) -> usize {
     let mut widgets = Vec::new();
-    if is_linux {
-        widgets.push(host());
-        widgets.push(kernel());
-        widgets.push(environment());
+    if host.linux {
+        widgets.push(system(&host.system));
+        widgets.push(kernel(&host.kernel, data.summary.uptime));
+        widgets.push(environment(&host.environment));
     }
 
-    widgets.push(packages(pms));
-    widgets.push(cpu(&data.cpu));
+    widgets.push(packages(&host.packages));
+    widgets.push(cpu(&data.cpu, &host.cpu));
     widgets.push(memory(&data.memory));
+    widgets.append(&mut data.batteries.iter().map(battery).collect());
 
-    for (i, widget) in widgets
-        .into_iter()
-        .enumerate()
-        .filter(|(i, _)| i < &areas.len())
-    {
-        frame.render_widget(widget, areas[i]);
-    }
+    cards(frame, area, config::get().layout.stats1, widgets, page)
 }
 
-pub fn draw_page_2(frame: &mut Frame, area: Rect, data: &Data) {
-    let is_linux = hw_linux::is_linux().unwrap_or(false);
-    let vertical = Layout::vertical([Constraint::Ratio(1, Y as u32); Y]);
-    let horizontal = Layout::horizontal([Constraint::Ratio(1, X_2 as u32); X_2]);
-    let v = vertical.areas::<Y>(area);
-    let mut areas = Vec::new();
-    for a in v {
-        for l in horizontal.areas::<X_2>(a) {
-            areas.push(l);
-        }
-    }
-
+/// Returns the number of pages the cards are split over.
+pub fn draw_page_2(
+    frame: &mut Frame,
+    area: Rect,
+    data: &Data,
+    host: &Host,
+    alerts: &Alerts,
+    page: usize,
+) -> usize {
     let mut widgets = Vec::new();
-
-    if is_linux {
-        let gpus = hw_linux::gpu::Gpus::get().unwrap();
-        widgets.append(&mut gpus.0.iter().enumerate().map(|(i, x)| gpu(x, i)).collect());
-    }
-
-    widgets.append(&mut data.disks.iter().map(|x| disk(x)).collect());
+    widgets.append(
+        &mut host
+            .gpus
+            .iter()
+            .enumerate()
+            .map(|(i, x)| gpu(x, i))
+            .collect(),
+    );
+
+    widgets.append(
+        &mut data
+            .disks
+            .iter()
+            .map(|x| {
+                let io = data
+                    .disk_io
+                    .iter()
+                    .find(|io| x.device.as_ref() == Some(&io.name));
+                disk(x, io, alerts)
+            })
+            .collect(),
+    );
     widgets.append(&mut data.networks.iter().map(|x| network(x)).collect());
 
-    for (i, widget) in widgets
-        .into_iter()
-        .enumerate()
-        .filter(|(i, _)| i < &areas.len())
-    {
-        frame.render_widget(widget, areas[i]);
-    }
+    cards(frame, area, config::get().layout.stats2, widgets, page)
 }
 
 fn memory(memory: &Memory) -> Table<'static> {
@@ -128,17 +123,16 @@ fn memory(memory: &Memory) -> Table<'static> {
     Table::new(rows, widths).block(block)
 }
 
-fn packages(pms: &PackageManagers) -> Table<'static> {
-    let rows = pms
-        .0
+fn packages(packages: &[Packages]) -> Table<'static> {
+    let rows = packages
         .windows(2)
         .step_by(2)
         .map(|pm| {
             Row::new(vec![
-                pm[0].name.clone(),
-                format!("{}", pm[0].packages),
-                pm[1].name.clone(),
-                format!("{}", pm[1].packages),
+                pm[0].manager.clone(),
+                format!("{}", pm[0].count),
+                pm[1].manager.clone(),
+                format!("{}", pm[1].count),
             ])
         })
         .collect::<Vec<Row>>();
@@ -153,16 +147,15 @@ fn packages(pms: &PackageManagers) -> Table<'static> {
     Table::new(rows, widths).block(block)
 }
 
-fn environment() -> Table<'static> {
-    let env_info = hw_linux::environment::EnvironmentInfo::get().unwrap();
+fn environment(env_info: &host::Environment) -> Table<'static> {
     let mut rows = Vec::new();
-    if let Some(user) = env_info.user {
+    if let Some(user) = &env_info.user {
         rows.push(Row::new(vec!["User".to_string(), user.to_string()]));
     }
-    if let Some(shell) = env_info.shell {
+    if let Some(shell) = &env_info.shell {
         rows.push(Row::new(vec!["Shell".to_string(), shell.to_string()]));
     }
-    if let Some(term) = env_info.term {
+    if let Some(term) = &env_info.term {
         rows.push(Row::new(vec!["Terminal".to_string(), term.to_string()]));
     }
     let widths = [Constraint::Percentage(20), Constraint::Fill(1)];
@@ -170,7 +163,7 @@ fn environment() -> Table<'static> {
     Table::new(rows, widths).block(block)
 }
 
-fn gpu(gpu: &hw_linux::gpu::Gpu, index: usize) -> Table<'static> {
+fn gpu(gpu: &host::Gpu, index: usize) -> Table<'static> {
     let mut rows = Vec::new();
     if let Some(name) = &gpu.name {
         rows.push(Row::new(vec!["Name".to_string(), name.to_string()]));
@@ -186,7 +179,7 @@ fn gpu(gpu: &hw_linux::gpu::Gpu, index: usize) -> Table<'static> {
     Table::new(rows, widths).block(block)
 }
 
-fn disk(disk: &Disk) -> Table<'static> {
+fn disk(disk: &Disk, io: Option<&DiskIo>, alerts: &Alerts) -> Table<'static> {
     let mut rows = Vec::new();
     rows.push(Row::new(vec![
         "Mount".to_string(),
@@ -200,9 +193,25 @@ fn disk(disk: &Disk) -> Table<'static> {
         format!("{:.2} Gb", disk.total_space / 1024 / 1024 / 1024),
     ]));
 
+    // Coloured like the alerts once the free space is under its thresholds.
+    let free = match disk.total_space {
+        0 => 100.0,
+        total => disk.free_space as f64 / total as f64 * 100.0,
+    };
     rows.push(Row::new(vec![
-        "Free".to_string(),
-        format!("{:.2} Gb", disk.free_space / 1024 / 1024 / 1024),
+        Cell::from("Free"),
+        Cell::from(
+            Line::from(format!(
+                "{:.2} Gb ({:.0}%)",
+                disk.free_space / 1024 / 1024 / 1024,
+                free
+            ))
+            .style(
+                alerts
+                    .level(&format!("Disk {} free", disk.mount_point.display()))
+                    .style(Color::Reset),
+            ),
+        ),
     ]));
 
     rows.push(Row::new(vec![
@@ -221,36 +230,91 @@ fn disk(disk: &Disk) -> Table<'static> {
         },
     ]));
 
+    if let Some(io) = io {
+        rows.push(Row::new(vec![
+            "Read".to_string(),
+            format!("{}/s ({:.0} IOPS)", get_bytes(io.read_rate), io.read_iops),
+        ]));
+
+        rows.push(Row::new(vec![
+            "Write".to_string(),
+            format!("{}/s ({:.0} IOPS)", get_bytes(io.write_rate), io.write_iops),
+        ]));
+    }
+
+    let widths = [Constraint::Percentage(20), Constraint::Fill(1)];
+    let title = match io {
+        Some(io) => format!("Disk {:?} ({:.0}% busy)", disk.name, io.utilisation),
+        None => format!("Disk {:?}", disk.name),
+    };
+    let block = get_block().title(title);
+    Table::new(rows, widths).block(block)
+}
+
+fn battery(battery: &Battery) -> Table<'static> {
+    let mut rows = Vec::new();
+    rows.push(Row::new(vec![
+        "Charge".to_string(),
+        format!("{:.0}%", battery.percent),
+    ]));
+
+    rows.push(Row::new(vec!["State".to_string(), battery.state.clone()]));
+
+    if let Some(time) = battery.time_to_empty {
+        rows.push(Row::new(vec!["Empty In".to_string(), get_time(time)]));
+    }
+
+    if let Some(time) = battery.time_to_full {
+        rows.push(Row::new(vec!["Full In".to_string(), get_time(time)]));
+    }
+
+    if let Some(watts) = battery.watts {
+        rows.push(Row::new(vec![
+            "Rate".to_string(),
+            format!("{:.2} W", watts),
+        ]));
+    }
+
+    if let Some(health) = battery.health {
+        rows.push(Row::new(vec![
+            "Health".to_string(),
+            format!("{:.0}%", health),
+        ]));
+    }
+
+    if let Some(cycles) = battery.cycles {
+        rows.push(Row::new(vec!["Cycles".to_string(), cycles.to_string()]));
+    }
+
     let widths = [Constraint::Percentage(20), Constraint::Fill(1)];
-    let block = get_block().title(format!("Disk {:?}", disk.name));
+    let block = get_block().title(format!("Battery {:?}", battery.name));
     Table::new(rows, widths).block(block)
 }
 
-fn host() -> Table<'static> {
-    let host_info = hw_linux::host::HostInfo::get().unwrap();
+fn system(host_info: &host::System) -> Table<'static> {
     let mut rows = Vec::new();
-    if let Some(model) = host_info.model {
+    if let Some(model) = &host_info.model {
         rows.push(Row::new(vec!["Device".to_string(), model.to_string()]));
     }
-    if let Some(arch) = host_info.architecture {
+    if let Some(arch) = &host_info.architecture {
         rows.push(Row::new(vec!["Arch".to_string(), arch.to_string()]));
     }
-    if let Some(vendor) = host_info.vendor {
+    if let Some(vendor) = &host_info.vendor {
         rows.push(Row::new(vec!["Vendor".to_string(), vendor.to_string()]));
     }
-    if let Some(os) = host_info.os {
+    if let Some(os) = &host_info.os {
         rows.push(Row::new(vec!["OS".to_string(), os.to_string()]));
     }
-    if let Some(distro) = host_info.distro {
+    if let Some(distro) = &host_info.distro {
         rows.push(Row::new(vec!["Distro".to_string(), distro.to_string()]));
     }
-    if let Some(de) = host_info.desktop_env {
+    if let Some(de) = &host_info.desktop_env {
         rows.push(Row::new(vec!["DE".to_string(), de.to_string()]));
     }
-    if let Some(wm) = host_info.win_manager {
+    if let Some(wm) = &host_info.win_manager {
         rows.push(Row::new(vec!["WM".to_string(), wm.to_string()]));
     }
-    if let Some(session) = host_info.session {
+    if let Some(session) = &host_info.session {
         rows.push(Row::new(vec!["Session".to_string(), session.to_string()]));
     }
 
@@ -259,23 +323,15 @@ fn host() -> Table<'static> {
     Table::new(rows, widths).block(block)
 }
 
-fn kernel() -> Table<'static> {
-    let kernel_info = hw_linux::environment::KernelInfo::get().unwrap();
-    let uptime_info = hw_linux::environment::UptimeInfo::get().unwrap_or_default();
-
+fn kernel(kernel_info: &host::Kernel, uptime: u64) -> Table<'static> {
     let mut rows = Vec::new();
-    if let Some(version) = kernel_info.version {
+    if let Some(version) = &kernel_info.version {
         rows.push(Row::new(vec!["Version".to_string(), version.to_string()]));
     }
-    if let Some(release) = kernel_info.release {
+    if let Some(release) = &kernel_info.release {
         rows.push(Row::new(vec!["Release".to_string(), release.to_string()]));
     }
-    if let Some(uptime_s) = uptime_info.0 {
-        rows.push(Row::new(vec![
-            "Uptime".to_string(),
-            get_time(uptime_s as u64),
-        ]));
-    }
+    rows.push(Row::new(vec!["Uptime".to_string(), get_time(uptime)]));
     let widths = [Constraint::Percentage(20), Constraint::Fill(1)];
     let block = get_block().title("Kernel");
     Table::new(rows, widths).block(block)
@@ -298,12 +354,34 @@ fn network(network: &Network) -> Table<'static> {
 
     rows.push(Row::new(vec![
         "Received".to_string(),
-        network.received.to_string(),
+        format!(
+            "{}/s ({:.0} pkt/s)",
+            get_bytes(network.rx_rate),
+            network.rx_packet_rate
+        ),
     ]));
 
     rows.push(Row::new(vec![
         "Transmitted".to_string(),
-        network.transmitted.to_string(),
+        format!(
+            "{}/s ({:.0} pkt/s)",
+            get_bytes(network.tx_rate),
+            network.tx_packet_rate
+        ),
+    ]));
+
+    rows.push(Row::new(vec![
+        "Total".to_string(),
+        format!(
+            "↓ {} ↑ {}",
+            get_bytes(network.total_received as f64),
+            get_bytes(network.total_transmitted as f64)
+        ),
+    ]));
+
+    rows.push(Row::new(vec![
+        "Errors".to_string(),
+        format!("↓ {} ↑ {}", network.rx_errors, network.tx_errors),
     ]));
 
     let widths = [Constraint::Percentage(20), Constraint::Fill(1)];
@@ -311,9 +389,7 @@ fn network(network: &Network) -> Table<'static> {
     Table::new(rows, widths).block(block)
 }
 
-fn cpu(cpu_info: &CpuInfo) -> Table<'static> {
-    let linux_cpu_info = hw_linux::cpu::CpuInfo::get().unwrap_or_default();
-
+fn cpu(cpu_info: &CpuInfo, linux_cpu_info: &host::Cpu) -> Table<'static> {
     let mut rows = Vec::new();
 
     rows.push(Row::new(vec![
@@ -345,19 +421,20 @@ fn cpu(cpu_info: &CpuInfo) -> Table<'static> {
         ]));
     }
 
-    if let (Some(freq), Some(max)) = (linux_cpu_info.cur_freq, linux_cpu_info.max_freq) {
+    let freq = cpu_info.cores.first().map_or(0, |x| x.frequency);
+    if let Some(max) = linux_cpu_info.max_frequency {
         rows.push(Row::new(vec![
             "Frequency".to_string(),
-            format!("{:.2} MHz / {:.0} MHz", freq, max),
+            format!("{} MHz / {:.0} MHz", freq, max),
         ]));
     } else {
         rows.push(Row::new(vec![
             "Frequency".to_string(),
-            format!("{:.2} MHz", cpu_info.cores[0].frequency),
+            format!("{} MHz", freq),
         ]));
     }
 
-    if let Some(temp) = linux_cpu_info.temp {
+    if let Some(temp) = cpu_info.temperature {
         rows.push(Row::new(vec![
             "Temp".to_string(),
             format!("{:.2} °C", temp),
@@ -0,0 +1,32 @@
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::{data::Summary, get_time, pages::get_block, theme};
+
+/// Rows taken by the header, one line of text inside a border.
+pub const HEIGHT: u16 = 3;
+
+pub fn draw(frame: &mut Frame, area: Rect, summary: &Summary) {
+    let label = |x: &'static str| Span::styled(x, Style::new().fg(theme::get().title).bold());
+    let [one, five, fifteen] = summary.load_average;
+
+    let line = Line::from(vec![
+        label("Load "),
+        Span::raw(format!("{:.2} {:.2} {:.2}", one, five, fifteen)),
+        label("  Uptime "),
+        Span::raw(get_time(summary.uptime)),
+        label("  Tasks "),
+        Span::raw(format!(
+            "{} ({} running, {} sleeping, {} zombie)",
+            summary.tasks, summary.running, summary.sleeping, summary.zombie
+        )),
+        label("  Threads "),
+        Span::raw(summary.threads.to_string()),
+    ]);
+    frame.render_widget(Paragraph::new(line).block(get_block()), area);
+}
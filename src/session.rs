@@ -0,0 +1,355 @@
+use flate2::{read::MultiGzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    data::{host::Host, Data},
+    keymap::{Action, Keymap, Scope},
+};
+
+/// The line a recorder starts with, what the stats pages show about the machine besides its
+/// samples.
+#[derive(Serialize, Deserialize)]
+struct Header {
+    host: Host,
+}
+
+/// Appends samples to a recording. Each sample is a line of JSON like `--json-lines` prints,
+/// gzipped on its own so a recording can be appended to and a write that is cut short only
+/// loses the last sample.
+pub struct Recorder {
+    file: File,
+    path: PathBuf,
+}
+
+impl Recorder {
+    /// Opens the recording at `path` and appends a header with `host`.
+    pub fn open(path: &Path, host: &Host) -> Result<Self, String> {
+        let mut recorder = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map(|file| Self {
+                file,
+                path: path.to_path_buf(),
+            })
+            .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+        recorder.append(&Header { host: host.clone() })?;
+        Ok(recorder)
+    }
+
+    /// Appends a sample. Fails if it can't be written, or if the recording was removed since
+    /// writes to a removed file succeed but are lost.
+    pub fn write(&mut self, data: &Data) -> Result<(), String> {
+        self.append(data)
+    }
+
+    fn append<T: Serialize>(&mut self, value: &T) -> Result<(), String> {
+        let write = |file: &mut File| -> io::Result<()> {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            serde_json::to_writer(&mut encoder, value)?;
+            writeln!(encoder)?;
+            file.write_all(&encoder.finish()?)
+        };
+        write(&mut self.file)
+            .map_err(|e| format!("failed to write {}: {}", self.path.display(), e))?;
+        match self.path.exists() {
+            true => Ok(()),
+            false => Err(format!("{} was removed", self.path.display())),
+        }
+    }
+}
+
+pub struct Recording {
+    /// From the first header, plain `--json-lines` output has none so the stats pages leave
+    /// out the machine.
+    pub host: Host,
+    pub samples: Vec<Data>,
+}
+
+/// Reads every sample of a recording, or of plain `--json-lines` output. Blank lines are
+/// skipped, as is a sample cut short at the end of a recording.
+pub fn load(path: &Path) -> Result<Recording, String> {
+    let bytes = fs::read(path).map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    let reader: Box<dyn BufRead> = match bytes.starts_with(&[0x1f, 0x8b]) {
+        true => Box::new(BufReader::new(MultiGzDecoder::new(bytes.as_slice()))),
+        false => Box::new(bytes.as_slice()),
+    };
+    let mut host = None;
+    let mut samples = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("failed to read {}: {}", path.display(), e)),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let error = |e| format!("failed to parse {} line {}: {}", path.display(), i + 1, e);
+        // Samples start with their timestamp, so only a header starts with its host.
+        if line.starts_with("{\"host\":") {
+            let header = serde_json::from_str::<Header>(&line).map_err(error)?;
+            host.get_or_insert(header.host);
+            continue;
+        }
+        samples.push(serde_json::from_str::<Data>(&line).map_err(error)?);
+    }
+    if samples.is_empty() {
+        return Err(format!("{} has no samples", path.display()));
+    }
+    Ok(Recording {
+        host: host.unwrap_or_default(),
+        samples,
+    })
+}
+
+enum Control {
+    Pause,
+    Forward,
+    Back,
+    Speed(f64),
+}
+
+const MIN_SPEED: f64 = 0.25;
+const MAX_SPEED: f64 = 16.0;
+
+/// Plays a recording into the app's data channel in place of the sampler thread.
+///
+/// Stepping back is done by the app dropping its newest sample, the player only moves its
+/// position back so the next step forward sends that sample again.
+pub struct Replay {
+    tx: Sender<Control>,
+    position: Arc<AtomicUsize>,
+    pub len: usize,
+    pub paused: bool,
+    pub speed: f64,
+}
+
+impl Replay {
+    pub fn start(samples: Vec<Data>, interval: Arc<AtomicU64>, dtx: Sender<Data>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let position = Arc::new(AtomicUsize::new(0));
+        let len = samples.len();
+
+        let sent = position.clone();
+        thread::spawn(move || {
+            let (mut pos, mut paused, mut speed) = (0, false, 1.0);
+            let mut next = Instant::now();
+            loop {
+                let playing = !paused && pos < samples.len();
+                if playing && Instant::now() >= next {
+                    let _ = dtx.send(samples[pos].clone());
+                    pos += 1;
+                    let wait = Duration::from_millis(interval.load(Ordering::Relaxed));
+                    next = Instant::now() + wait.div_f64(speed);
+                    sent.store(pos, Ordering::Relaxed);
+                }
+
+                let timeout = match !paused && pos < samples.len() {
+                    true => next.saturating_duration_since(Instant::now()),
+                    false => Duration::from_secs(3600),
+                };
+                match rx.recv_timeout(timeout) {
+                    Ok(Control::Pause) => {
+                        paused = !paused;
+                        next = Instant::now();
+                    }
+                    Ok(Control::Forward) => {
+                        paused = true;
+                        if pos < samples.len() {
+                            let _ = dtx.send(samples[pos].clone());
+                            pos += 1;
+                        }
+                    }
+                    Ok(Control::Back) => {
+                        paused = true;
+                        pos = pos.saturating_sub(1).max(1);
+                    }
+                    Ok(Control::Speed(x)) => speed = x,
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+                sent.store(pos, Ordering::Relaxed);
+            }
+        });
+
+        Self {
+            tx,
+            position,
+            len,
+            paused: false,
+            speed: 1.0,
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position.load(Ordering::Relaxed)
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        let _ = self.tx.send(Control::Pause);
+    }
+
+    pub fn forward(&mut self) {
+        self.paused = true;
+        let _ = self.tx.send(Control::Forward);
+    }
+
+    pub fn back(&mut self) {
+        self.paused = true;
+        let _ = self.tx.send(Control::Back);
+    }
+
+    pub fn change_speed(&mut self, factor: f64) {
+        self.speed = (self.speed * factor).clamp(MIN_SPEED, MAX_SPEED);
+        let _ = self.tx.send(Control::Speed(self.speed));
+    }
+
+    pub fn status(&self, keymap: &Keymap) -> String {
+        let state = match (self.paused, self.position() >= self.len) {
+            (_, true) => "■",
+            (true, false) => "⏸",
+            (false, false) => "▶",
+        };
+        format!(
+            " | Replay {}/{} {} {}x | {}",
+            self.position(),
+            self.len,
+            state,
+            self.speed,
+            keymap.hints(
+                Scope::Replay,
+                &[
+                    ("Pause", &[Action::Pause]),
+                    ("Step", &[Action::StepBack, Action::StepForward]),
+                    ("Speed", &[Action::ReplaySlower, Action::ReplayFaster]),
+                ]
+            )
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixture::{sample, TempDir};
+
+    fn timestamps(samples: &[Data]) -> Vec<u64> {
+        samples.iter().map(|x| x.timestamp).collect()
+    }
+
+    #[test]
+    fn recordings_are_appended_to() {
+        let dir = TempDir::new("record");
+        let path = dir.path().join("session");
+        let mut recorder = Recorder::open(&path, &Host::default()).unwrap();
+        recorder.write(&sample(1, 10.0)).unwrap();
+        recorder.write(&sample(2, 20.0)).unwrap();
+        drop(recorder);
+        Recorder::open(&path, &Host::default())
+            .unwrap()
+            .write(&sample(3, 30.0))
+            .unwrap();
+
+        let samples = load(&path).unwrap().samples;
+        assert_eq!(timestamps(&samples), [1, 2, 3]);
+        assert_eq!(samples[2].cpu.usage, 30.0);
+    }
+
+    #[test]
+    fn recordings_keep_the_first_host() {
+        let dir = TempDir::new("host");
+        let path = dir.path().join("session");
+        let mut host = Host {
+            linux: true,
+            ..Default::default()
+        };
+        host.kernel.release = Some("6.1.0".to_string());
+        Recorder::open(&path, &host)
+            .unwrap()
+            .write(&sample(1, 10.0))
+            .unwrap();
+        Recorder::open(&path, &Host::default())
+            .unwrap()
+            .write(&sample(2, 10.0))
+            .unwrap();
+
+        let recording = load(&path).unwrap();
+        assert_eq!(timestamps(&recording.samples), [1, 2]);
+        assert!(recording.host.linux);
+        assert_eq!(recording.host.kernel.release.as_deref(), Some("6.1.0"));
+    }
+
+    #[test]
+    fn recordings_are_compressed() {
+        let dir = TempDir::new("compressed");
+        let path = dir.path().join("session");
+        let mut recorder = Recorder::open(&path, &Host::default()).unwrap();
+        let mut data = sample(1, 10.0);
+        data.processes = (0..500)
+            .map(|i| crate::data::Process {
+                user: "root".to_string(),
+                name: format!("worker-{}", i).into(),
+                pid: sysinfo::Pid::from_u32(i),
+                parent: Some(sysinfo::Pid::from_u32(1)),
+                memory: 1 << 20,
+                cpu: 0.5,
+                command: format!("/usr/bin/worker --id {}", i),
+                run_time: 3600,
+                total_m: 1 << 34,
+                disk_read: 0.0,
+                disk_write: 0.0,
+            })
+            .collect();
+        recorder.write(&data).unwrap();
+
+        let json = serde_json::to_vec(&data).unwrap();
+        let size = fs::metadata(&path).unwrap().len() as usize;
+        assert!(size * 5 < json.len(), "{} of {}", size, json.len());
+        assert_eq!(load(&path).unwrap().samples[0].processes.len(), 500);
+    }
+
+    #[test]
+    fn a_sample_cut_short_is_skipped() {
+        let dir = TempDir::new("truncated");
+        let path = dir.path().join("session");
+        let mut recorder = Recorder::open(&path, &Host::default()).unwrap();
+        for i in 1..=3 {
+            recorder.write(&sample(i, 10.0)).unwrap();
+        }
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() - 20]).unwrap();
+
+        assert_eq!(timestamps(&load(&path).unwrap().samples), [1, 2]);
+    }
+
+    #[test]
+    fn json_lines_can_be_replayed() {
+        let dir = TempDir::new("json-lines");
+        let path = dir.path().join("session.jsonl");
+        let lines = [sample(1, 10.0), sample(2, 20.0)]
+            .iter()
+            .map(|x| serde_json::to_string(x).unwrap())
+            .collect::<Vec<String>>();
+        fs::write(&path, format!("{}\n\n{}\n", lines[0], lines[1])).unwrap();
+
+        let recording = load(&path).unwrap();
+        assert_eq!(timestamps(&recording.samples), [1, 2]);
+        assert!(!recording.host.linux);
+        fs::write(&path, "\n").unwrap();
+        assert!(load(&path).is_err());
+    }
+}
@@ -0,0 +1,48 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+
+use crate::data::Sampler;
+
+/// Prints `count` samples as JSON without starting the TUI. `lines` prints one compact
+/// object per line, otherwise each sample is pretty printed. No `count` means forever.
+pub fn print(
+    lines: bool,
+    count: Option<usize>,
+    interval: Duration,
+    sysfs: PathBuf,
+) -> io::Result<()> {
+    let mut sampler = Sampler::new(sysfs);
+    // CPU usage is computed from the difference between two refreshes.
+    thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    sampler.refresh();
+
+    let mut stdout = io::stdout().lock();
+    let mut printed = 0;
+    loop {
+        let data = sampler.sample();
+        let result = match lines {
+            true => serde_json::to_writer(&mut stdout, &data),
+            false => serde_json::to_writer_pretty(&mut stdout, &data),
+        };
+        // A closed pipe (e.g. `monitors --json-lines | head`) just means we are done.
+        match result
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(stdout))
+            .and_then(|_| stdout.flush())
+        {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            result => result?,
+        }
+
+        printed += 1;
+        if count.is_some_and(|x| printed >= x) {
+            return Ok(());
+        }
+        thread::sleep(interval);
+        sampler.refresh();
+    }
+}
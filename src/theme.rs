@@ -0,0 +1,264 @@
+use ratatui::style::{Color, Style, Stylize};
+use std::{env, sync::OnceLock};
+
+use crate::config::Config;
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// The theme picked at startup, or the dark theme if none has been picked.
+pub fn get() -> &'static Theme {
+    THEME.get_or_init(Theme::dark)
+}
+
+pub fn set(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+pub const BUILT_IN: [&str; 4] = ["dark", "light", "high-contrast", "monochrome"];
+
+/// The colours every page draws with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// Block titles and labels.
+    pub title: Color,
+    pub border: Color,
+    /// The background of selected rows, `Color::Reset` reverses them instead.
+    pub highlight: Color,
+    pub highlight_text: Color,
+    pub gauge: Color,
+    pub core_gauge: Color,
+    pub warning: Color,
+    pub critical: Color,
+    /// Messages about something that worked, e.g. a signal being sent.
+    pub good: Color,
+    /// The cursor line on the history charts.
+    pub cursor: Color,
+    /// The minimum and maximum of downsampled history.
+    pub band: Color,
+    /// The main line of a chart, e.g. CPU or memory.
+    pub primary: Color,
+    /// The second line of a chart, e.g. swap.
+    pub secondary: Color,
+    /// Charts with a line per core, device or sensor cycle through these.
+    pub series: Vec<Color>,
+    /// The heat-map cells from idle to saturated.
+    pub heat: Vec<Color>,
+    pub heat_text: Color,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            title: Color::Blue,
+            border: Color::Reset,
+            highlight: Color::Blue,
+            highlight_text: Color::Reset,
+            gauge: Color::Green,
+            core_gauge: Color::Blue,
+            warning: Color::Yellow,
+            critical: Color::Red,
+            good: Color::Green,
+            cursor: Color::White,
+            band: Color::DarkGray,
+            primary: Color::Blue,
+            secondary: Color::Yellow,
+            series: vec![
+                Color::Red,
+                Color::Green,
+                Color::Yellow,
+                Color::Blue,
+                Color::Magenta,
+                Color::Cyan,
+                Color::LightRed,
+                Color::LightGreen,
+                Color::LightYellow,
+                Color::LightBlue,
+                Color::LightMagenta,
+                Color::LightCyan,
+            ],
+            heat: vec![
+                Color::Green,
+                Color::LightGreen,
+                Color::Yellow,
+                Color::LightRed,
+                Color::Red,
+            ],
+            heat_text: Color::Black,
+        }
+    }
+
+    /// Darker colours that stay readable on a light background.
+    pub fn light() -> Self {
+        Self {
+            title: Color::Blue,
+            border: Color::Reset,
+            highlight: Color::Indexed(153),
+            highlight_text: Color::Black,
+            gauge: Color::Indexed(28),
+            core_gauge: Color::Blue,
+            warning: Color::Indexed(166),
+            critical: Color::Red,
+            good: Color::Indexed(28),
+            cursor: Color::Black,
+            band: Color::Gray,
+            primary: Color::Blue,
+            secondary: Color::Indexed(130),
+            series: vec![
+                Color::Red,
+                Color::Indexed(28),
+                Color::Indexed(130),
+                Color::Blue,
+                Color::Magenta,
+                Color::Indexed(30),
+                Color::Indexed(88),
+                Color::Indexed(54),
+            ],
+            heat: vec![
+                Color::Indexed(28),
+                Color::Indexed(70),
+                Color::Indexed(178),
+                Color::Indexed(166),
+                Color::Red,
+            ],
+            heat_text: Color::White,
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            title: Color::White,
+            border: Color::White,
+            highlight: Color::White,
+            highlight_text: Color::Black,
+            gauge: Color::LightGreen,
+            core_gauge: Color::LightCyan,
+            warning: Color::LightYellow,
+            critical: Color::LightRed,
+            good: Color::LightGreen,
+            cursor: Color::White,
+            band: Color::Gray,
+            primary: Color::LightCyan,
+            secondary: Color::LightYellow,
+            series: vec![
+                Color::LightCyan,
+                Color::LightYellow,
+                Color::LightMagenta,
+                Color::LightGreen,
+                Color::LightRed,
+                Color::White,
+            ],
+            heat: vec![Color::LightGreen, Color::LightYellow, Color::LightRed],
+            heat_text: Color::Black,
+        }
+    }
+
+    /// No colours at all, used when `NO_COLOR` is set. Warnings and critical values are set
+    /// apart by bold and underlined text instead.
+    pub fn monochrome() -> Self {
+        Self {
+            title: Color::Reset,
+            border: Color::Reset,
+            highlight: Color::Reset,
+            highlight_text: Color::Reset,
+            gauge: Color::Reset,
+            core_gauge: Color::Reset,
+            warning: Color::Reset,
+            critical: Color::Reset,
+            good: Color::Reset,
+            cursor: Color::Reset,
+            band: Color::Reset,
+            primary: Color::Reset,
+            secondary: Color::Reset,
+            series: Vec::new(),
+            heat: Vec::new(),
+            heat_text: Color::Reset,
+        }
+    }
+
+    fn built_in(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// The theme named on the command line or in the config, with the `[colors]` overrides
+    /// applied. Without either, `NO_COLOR` picks the monochrome theme.
+    pub fn resolve(config: &Config, name: Option<&str>) -> Result<Self, String> {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|x| !x.is_empty());
+        let name = match (name.or(config.theme.as_deref()), no_color) {
+            (Some(name), _) => name,
+            (None, true) => "monochrome",
+            (None, false) => "dark",
+        };
+
+        let mut theme = match (Self::built_in(name), config.themes.get(name)) {
+            (Some(theme), _) => theme,
+            (None, Some(custom)) => {
+                let mut theme = Self::built_in(&custom.base).ok_or(format!(
+                    "theme '{}' is based on unknown theme '{}', expected one of: {}",
+                    name,
+                    custom.base,
+                    BUILT_IN.join(", ")
+                ))?;
+                custom.colors.apply(&mut theme);
+                theme
+            }
+            (None, None) => {
+                let mut names = BUILT_IN.map(String::from).to_vec();
+                names.extend(config.themes.keys().cloned());
+                return Err(format!(
+                    "unknown theme '{}', expected one of: {}",
+                    name,
+                    names.join(", ")
+                ));
+            }
+        };
+        config.colors.apply(&mut theme);
+        Ok(theme)
+    }
+
+    /// The style of selected rows.
+    pub fn highlight_style(&self) -> Style {
+        match self.highlight {
+            Color::Reset => Style::new().reversed(),
+            color => Style::new().bg(color).fg(self.highlight_text),
+        }
+    }
+
+    /// The style of a warning, bold when the theme has no colour for it.
+    pub fn warning_style(&self) -> Style {
+        match self.warning {
+            Color::Reset => Style::new().bold(),
+            color => Style::new().fg(color),
+        }
+    }
+
+    /// The style of something critical or failed, bold and underlined when the theme has no
+    /// colour for it.
+    pub fn critical_style(&self) -> Style {
+        match self.critical {
+            Color::Reset => Style::new().bold().underlined(),
+            color => Style::new().fg(color),
+        }
+    }
+
+    /// The `i`th colour of `series`, wrapping around.
+    pub fn series(&self, i: usize) -> Color {
+        match self.series.len() {
+            0 => Color::Reset,
+            n => self.series[i % n],
+        }
+    }
+
+    /// The heat-map colour of a percentage.
+    pub fn heat(&self, percent: f64) -> Color {
+        match self.heat.len() {
+            0 => Color::Reset,
+            n => self.heat[((percent / 100.0 * n as f64) as usize).min(n - 1)],
+        }
+    }
+}